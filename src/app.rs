@@ -1,42 +1,86 @@
+use crate::command::Command;
+use crate::command::Filter;
+use crate::command::LabelOp;
+use crate::command::Sort;
+use crate::command::Target;
+use crate::config::Config;
+use crate::config::PaneLayout;
 use crate::config::Theme;
+use crate::config::resolve_theme;
+use crate::config::save_layout;
+use crate::geoip::GeoIp;
+use crate::keymap::Action;
+use crate::keymap::Context;
+use crate::keymap::Keymap;
+use crate::session::SessionClient;
+use crate::session::SessionStatus;
+use crate::ui_state::UiState;
+use crate::util::expand_path;
+use crate::widgets::add_options::AddOptions;
+use crate::widgets::batch_adder::BatchAdder;
+use crate::widgets::command_line::CommandLine;
 use crate::widgets::custome_tab::CustomeTabs;
 use crate::widgets::delete_popup::DeletePopup;
+use crate::widgets::duplicate_popup::DuplicatePopup;
 use crate::widgets::file_picker::FilePicker;
 use crate::widgets::files_table::FilesTable;
+use crate::widgets::help::Help;
 use crate::widgets::magnet::Magnet;
 use crate::widgets::peers_table::PeersTable;
+use crate::widgets::settings::Settings;
+use crate::widgets::stats_view::StatsView;
+use crate::widgets::status_bar::StatusBar;
+use crate::widgets::theme_picker::ThemePicker;
 use crate::widgets::torrent_actions::TorrentActions;
 use crate::widgets::torrent_adder::TorrentAdder;
+use crate::widgets::torrent_creator::TorrentCreator;
 use crate::widgets::torrent_details::Details;
 use crate::widgets::torrent_table::TorrentTable;
 
 use color_eyre::Result;
 use crossterm::event;
 use crossterm::event::Event;
-use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
-use crossterm::event::KeyModifiers;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use dirs::home_dir;
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
 use ratatui::layout::Constraint::Length;
+use ratatui::layout::Constraint::Min;
 use ratatui::layout::Constraint::Percentage;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
+use ratatui::layout::Position;
+use ratatui::layout::Rect;
 use ratatui::style::Style;
 use ratatui::widgets::Block;
-use ratatui::widgets::ScrollbarState;
-use ratatui::widgets::TableState;
 use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 use tokio::sync::Mutex;
 use transmission_rpc::types::Id;
+use transmission_rpc::types::RpcResponse;
+use transmission_rpc::types::RpcResponseArgument;
+use transmission_rpc::types::SessionSetArgs;
+use transmission_rpc::types::SessionStats;
 use transmission_rpc::types::Torrent;
 use transmission_rpc::types::TorrentAction;
+use transmission_rpc::types::TorrentAddArgs;
+use transmission_rpc::types::TorrentSetArgs;
 use transmission_rpc::types::TorrentStatus;
+use transmission_rpc::types::TrackerList;
+
+/// Clicks on the same cell within this long count as a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// Percent the split between the panes moves per grow or shrink.
+const SPLIT_STEP: u16 = 5;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum Pane {
@@ -113,8 +157,14 @@ pub enum PopUp {
     TorrentAction(TorrentActions),
     DeleteConfirmation(DeletePopup),
     FilePicker,
-    TorrentAdder(TorrentAdder),
-    AddMagnet(Magnet),
+    TorrentAdder(Box<TorrentAdder>),
+    AddMagnet(Box<Magnet>),
+    Duplicate(DuplicatePopup),
+    BatchAdder(Box<BatchAdder>),
+    CreateTorrent(Box<TorrentCreator>),
+    Settings(Box<Settings>),
+    Stats(Box<StatsView>),
+    ThemePicker(Box<ThemePicker>),
 }
 
 pub struct BottomPane {
@@ -131,22 +181,65 @@ pub struct App {
     top_table: TorrentTable,
     bottom_tab: CustomeTabs,
     bottom_pane: BottomPane,
+    /// Where the bottom pane was last drawn, for mouse clicks.
+    bottom_area: Rect,
     active_pane: Pane,
     popup: Option<PopUp>,
     file_picker: FilePicker,
     theme: Theme,
+    theme_name: String,
+    upload_torrent_files: bool,
+    local_daemon: bool,
+    session: SessionClient,
+    session_status: Arc<Mutex<SessionStatus>>,
+    /// Filled by the watch dir task, drained every frame.
+    watch_errors: Arc<Mutex<Vec<String>>>,
+    status_bar: StatusBar,
+    keymap: Keymap,
+    /// Key overview drawn above everything else, including popups.
+    help: Option<Help>,
+    command_line: CommandLine,
+    sort: Option<Sort>,
+    filter: Filter,
+    last_click: Option<(Instant, u16, u16)>,
+    layout: PaneLayout,
+    /// The focused pane takes all the space above the status bar.
+    zoomed: bool,
 }
 
 impl App {
     pub fn new(
         client: Arc<Mutex<transmission_rpc::TransClient>>,
         all_torrents: Arc<Mutex<Vec<Torrent>>>,
-        theme: Theme,
+        session_status: Arc<Mutex<SessionStatus>>,
+        watch_errors: Arc<Mutex<Vec<String>>>,
+        config: Config,
     ) -> Self {
-        App {
+        let geoip = config.geoip_db.as_deref().and_then(GeoIp::open);
+        let ui_state = UiState::load();
+        let picker_dir = ui_state
+            .picker_dir
+            .filter(|dir| Path::new(dir).is_dir())
+            .unwrap_or_else(|| home_dir().unwrap().to_str().unwrap().to_string());
+        let mut app = App {
             client,
             all_torrents,
-            theme,
+            theme: config.theme,
+            theme_name: config.theme_name,
+            upload_torrent_files: config.upload_torrent_files,
+            local_daemon: config.local_daemon,
+            session: SessionClient::new(config.rpc_config),
+            session_status,
+            watch_errors,
+            status_bar: StatusBar::default(),
+            keymap: config.keys,
+            help: None,
+            command_line: CommandLine::new(),
+            sort: ui_state.sort.and_then(|s| s.parse().ok()),
+            filter: ui_state.filter.parse().unwrap_or_default(),
+            last_click: None,
+            layout: config.layout,
+            zoomed: false,
             top_tab: CustomeTabs::new(
                 vec![
                     TopTab::All.to_string(),
@@ -164,21 +257,24 @@ impl App {
                 ],
                 false,
             ),
-            top_table: TorrentTable {
-                torrents: vec![],
-                state: TableState::default(),
-                scrollbar_state: ScrollbarState::default(),
-            },
+            top_table: TorrentTable::new(),
             bottom_pane: BottomPane {
                 details_block: Details::new(),
                 files_table: FilesTable::new(),
-                peers_table: PeersTable::new(),
+                peers_table: PeersTable::new(geoip),
             },
-            file_picker: FilePicker::new(home_dir().unwrap().to_str().unwrap().to_string(), false),
+            bottom_area: Rect::default(),
+            file_picker: FilePicker::new(picker_dir, false).with_bookmarks(config.bookmarks),
             active_pane: Pane::Top,
             popup: None,
             running: true,
+        };
+        app.top_tab.select(ui_state.top_tab);
+        app.bottom_tab.select(ui_state.bottom_tab);
+        if let Some(hash) = ui_state.selected {
+            app.top_table.select_hash(hash);
         }
+        app
     }
 
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
@@ -186,6 +282,9 @@ impl App {
         while self.running {
             self.filter_torrents().await;
             self.set_data_bottom_pane().await;
+            self.poll_torrent_creator().await;
+            self.show_watch_errors().await;
+            self.update_status_bar().await;
             terminal.draw(|frame| {
                 frame.render_widget(
                     Block::default()
@@ -196,56 +295,122 @@ impl App {
             })?;
             self.handle_crossterm_events().await?;
         }
+        self.save_ui_state();
         Ok(())
     }
 
+    fn save_ui_state(&self) {
+        UiState {
+            top_tab: self.top_tab.selected(),
+            bottom_tab: self.bottom_tab.selected(),
+            selected: self.top_table.selected_hash().map(str::to_string),
+            sort: self.sort.map(|s| s.to_string()),
+            filter: self.filter.to_string(),
+            picker_dir: Some(self.file_picker.path().to_string()),
+        }
+        .save();
+    }
+
     fn render(&mut self, frame: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
-            .constraints(vec![Length(3), Percentage(47), Length(3), Percentage(47)])
+            .constraints(vec![Min(0), Length(1)])
             .split(frame.area());
+        let (top, bottom) = self.pane_areas(chunks[0]);
 
-        self.top_tab.render(frame, chunks[0], &self.theme);
-        self.top_table.render(frame, chunks[1], &self.theme);
-        self.bottom_tab.render(frame, chunks[2], &self.theme);
+        // Hidden panes forget their areas so clicks don't land on them.
+        if top.is_empty() {
+            self.top_tab.area = Rect::default();
+            self.top_table.area = Rect::default();
+        } else {
+            let top = split_tabs(top);
+            self.top_tab.render(frame, top[0], &self.theme);
+            self.top_table.render(frame, top[1], &self.theme);
+        }
 
-        match self.bottom_tab.selected_tab().parse().unwrap() {
-            BottomTab::Details => {
-                self.bottom_pane
-                    .details_block
-                    .render(frame, chunks[3], &self.theme)
-            }
-            BottomTab::Peers => {
-                self.bottom_pane
-                    .peers_table
-                    .render(frame, chunks[3], &self.theme);
-            }
-            BottomTab::Files => {
-                self.bottom_pane
-                    .files_table
-                    .render(frame, chunks[3], &self.theme);
+        if bottom.is_empty() {
+            self.bottom_tab.area = Rect::default();
+            self.bottom_area = Rect::default();
+        } else {
+            let bottom = split_tabs(bottom);
+            self.bottom_tab.render(frame, bottom[0], &self.theme);
+            self.bottom_area = bottom[1];
+
+            match self.bottom_tab.selected_tab().parse().unwrap() {
+                BottomTab::Details => {
+                    self.bottom_pane
+                        .details_block
+                        .render(frame, bottom[1], &self.theme)
+                }
+                BottomTab::Peers => {
+                    self.bottom_pane
+                        .peers_table
+                        .render(frame, bottom[1], &self.theme);
+                }
+                BottomTab::Files => {
+                    self.bottom_pane
+                        .files_table
+                        .render(frame, bottom[1], &self.theme);
+                }
             }
         }
 
-        if self.popup.is_none() {
-            return;
+        if !self.command_line.render(frame, chunks[1], &self.theme) {
+            self.status_bar
+                .render(frame, chunks[1], &self.theme, &self.keymap);
+        }
+
+        if let Some(popup) = self.popup.as_mut() {
+            match popup {
+                PopUp::TorrentAction(ta) => ta.render(frame, &self.theme),
+                PopUp::DeleteConfirmation(dc) => dc.render(frame, &self.theme, &self.keymap),
+                PopUp::FilePicker => self.file_picker.render(frame, &self.theme, &self.keymap),
+                PopUp::TorrentAdder(ta) => ta.render(frame, &self.theme, &self.keymap),
+                PopUp::AddMagnet(am) => am.render(frame, &self.theme, &self.keymap),
+                PopUp::Duplicate(d) => d.render(frame, &self.theme),
+                PopUp::BatchAdder(ba) => ba.render(frame, &self.theme, &self.keymap),
+                PopUp::CreateTorrent(tc) => tc.render(frame, &self.theme, &self.keymap),
+                PopUp::Settings(s) => s.render(frame, &self.theme, &self.keymap),
+                PopUp::Stats(s) => s.render(frame, &self.theme, &self.keymap),
+                PopUp::ThemePicker(tp) => tp.render(frame, &self.theme, &self.keymap),
+            }
         }
-        match self.popup.as_mut().unwrap() {
-            PopUp::TorrentAction(ta) => ta.render(frame, &self.theme),
-            PopUp::DeleteConfirmation(dc) => dc.render(frame, &self.theme),
-            PopUp::FilePicker => self.file_picker.render(frame, &self.theme),
-            PopUp::TorrentAdder(ta) => ta.render(frame, &self.theme),
-            PopUp::AddMagnet(am) => am.render(frame, &self.theme),
+        if let Some(help) = self.help.as_mut() {
+            help.render(frame, &self.theme, &self.keymap);
         }
     }
 
+    /// Splits the space above the status bar between the torrent list and the
+    /// bottom pane. With a pane zoomed the other one gets an empty area.
+    fn pane_areas(&self, area: Rect) -> (Rect, Rect) {
+        if self.zoomed {
+            return match self.active_pane {
+                Pane::Top => (area, Rect::default()),
+                Pane::Bottom => (Rect::default(), area),
+            };
+        }
+        let direction = if self.layout.horizontal {
+            Direction::Horizontal
+        } else {
+            Direction::Vertical
+        };
+        let chunks = Layout::default()
+            .direction(direction)
+            .constraints(vec![
+                Percentage(self.layout.split),
+                Percentage(100 - self.layout.split),
+            ])
+            .split(area);
+        (chunks[0], chunks[1])
+    }
+
     async fn handle_crossterm_events(&mut self) -> Result<()> {
         if event::poll(Duration::from_millis(100))? {
             match event::read()? {
                 Event::Paste(_) => {}
                 Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key).await,
-                Event::Mouse(_) => {}
+                Event::Mouse(mouse) => self.on_mouse_event(mouse).await,
                 Event::Resize(_, _) => {}
                 _ => {}
             }
@@ -254,6 +419,28 @@ impl App {
     }
 
     async fn on_key_event(&mut self, key: KeyEvent) {
+        if let Some(help) = self.help.as_mut() {
+            if help.handler(key, &self.keymap) {
+                self.help = None;
+            }
+            return;
+        }
+        if self.command_line.is_active() {
+            let contexts = self.pane_contexts();
+            if let Some(line) = self.command_line.handler(key, &contexts) {
+                self.run_command(&line).await;
+            }
+            return;
+        }
+        self.command_line.clear_message();
+
+        if self.popup.is_some()
+            && let Some(contexts) = self.help_contexts()
+            && self.keymap.action(contexts[0].1, &key) == Some(Action::Help)
+        {
+            self.show_help();
+            return;
+        }
         if self.popup.is_some() {
             match self.popup.as_ref().unwrap() {
                 PopUp::DeleteConfirmation(_) => self.handle_popup_delete(key).await,
@@ -261,106 +448,487 @@ impl App {
                 PopUp::FilePicker => self.handle_filepicker(key).await,
                 PopUp::TorrentAdder(_) => self.handle_torrent_adder(key).await,
                 PopUp::AddMagnet(_) => self.handle_magnet_adder(key).await,
+                PopUp::Duplicate(_) => self.handle_duplicate(key).await,
+                PopUp::BatchAdder(_) => self.handle_batch_adder(key).await,
+                PopUp::CreateTorrent(_) => self.handle_torrent_creator(key).await,
+                PopUp::Settings(_) => self.handle_settings(key).await,
+                PopUp::Stats(_) => self.handle_stats(key).await,
+                PopUp::ThemePicker(_) => self.handle_theme_picker(key),
             }
             return;
         }
-        if key.code == KeyCode::Char('q') {
-            self.running = false;
+        if let Some(action) = self.keymap.action(Context::Global, &key) {
+            self.global_action(action).await;
             return;
         }
-        if key.code == KeyCode::Char('a') {
-            self.popup = Some(PopUp::FilePicker);
-            return;
+        match self.active_pane {
+            Pane::Top => self.handle_top_pane(key).await,
+            Pane::Bottom => self.handle_bottom_pane(key).await,
         }
-        if key.code == KeyCode::Char('m') {
-            self.popup = Some(PopUp::AddMagnet(Magnet::new()));
+    }
+
+    async fn on_mouse_event(&mut self, mouse: MouseEvent) {
+        // Overlays and text input stay keyboard only.
+        if self.help.is_some() || self.command_line.is_active() {
             return;
         }
+        let (column, row) = (mouse.column, mouse.row);
+        let double_click = mouse.kind == MouseEventKind::Down(MouseButton::Left)
+            && self.register_click(column, row);
+
+        match self.popup.as_mut() {
+            None => {}
+            Some(PopUp::TorrentAction(menu)) => {
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                    if menu.select_at(column, row) {
+                        self.run_menu_action().await;
+                    } else if !menu.contains(column, row) {
+                        self.popup = None;
+                    }
+                }
+                return;
+            }
+            Some(PopUp::FilePicker) => {
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left)
+                        if self.file_picker.select_at(column, row) && double_click =>
+                    {
+                        let (close, paths) = self.file_picker.select_entry().await;
+                        self.picked_torrents(close, paths).await;
+                    }
+                    MouseEventKind::ScrollDown => self.file_picker.scroll(true),
+                    MouseEventKind::ScrollUp => self.file_picker.scroll(false),
+                    _ => {}
+                }
+                return;
+            }
+            Some(_) => return,
+        }
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.click(column, row, double_click).await,
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some(index) = self.top_table.row_at(column, row) {
+                    self.top_table.select(Some(index));
+                    self.focus_pane(Pane::Top);
+                    self.top_pane_action(Action::OpenMenu).await;
+                    if let Some(PopUp::TorrentAction(menu)) = self.popup.as_mut() {
+                        menu.anchor = Some(Position::new(column, row));
+                    }
+                }
+            }
+            MouseEventKind::ScrollDown => self.scroll(column, row, true),
+            MouseEventKind::ScrollUp => self.scroll(column, row, false),
+            _ => {}
+        }
+    }
+
+    /// Records a left click, returns whether it completes a double-click.
+    fn register_click(&mut self, column: u16, row: u16) -> bool {
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(at, c, r)| {
+            c == column && r == row && now.duration_since(at) < DOUBLE_CLICK
+        });
+        self.last_click = (!double).then_some((now, column, row));
+        double
+    }
+
+    async fn click(&mut self, column: u16, row: u16, double_click: bool) {
+        let position = Position::new(column, row);
+        if let Some(index) = self.top_tab.tab_at(column, row) {
+            self.top_tab.select(index);
+            self.focus_pane(Pane::Top);
+        } else if let Some(index) = self.bottom_tab.tab_at(column, row) {
+            self.bottom_tab.select(index);
+            self.focus_pane(Pane::Bottom);
+        } else if self.top_table.area.contains(position) {
+            self.focus_pane(Pane::Top);
+            if let Some(index) = self.top_table.row_at(column, row) {
+                self.top_table.select(Some(index));
+                if double_click {
+                    self.top_pane_action(Action::OpenMenu).await;
+                }
+            }
+        } else if self.bottom_area.contains(position) {
+            self.focus_pane(Pane::Bottom);
+            match self.bottom_tab.selected_tab().parse().unwrap() {
+                BottomTab::Details => {}
+                BottomTab::Peers => self.bottom_pane.peers_table.select_at(column, row),
+                BottomTab::Files => self.bottom_pane.files_table.select_at(column, row),
+            }
+        }
+    }
+
+    fn scroll(&mut self, column: u16, row: u16, down: bool) {
+        let position = Position::new(column, row);
+        if self.top_table.area.contains(position) {
+            if down {
+                self.top_table.select_next();
+            } else {
+                self.top_table.select_prev();
+            }
+        } else if self.bottom_area.contains(position) {
+            let pane = &mut self.bottom_pane;
+            match (self.bottom_tab.selected_tab().parse().unwrap(), down) {
+                (BottomTab::Details, _) => {}
+                (BottomTab::Peers, true) => pane.peers_table.select_next(),
+                (BottomTab::Peers, false) => pane.peers_table.select_prev(),
+                (BottomTab::Files, true) => pane.files_table.select_next(),
+                (BottomTab::Files, false) => pane.files_table.select_prev(),
+            }
+        }
+    }
+
+    async fn global_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.running = false,
+            Action::AddTorrent => self.popup = Some(PopUp::FilePicker),
+            Action::AddMagnet => {
+                let options = self.add_options().await;
+                self.popup = Some(PopUp::AddMagnet(Box::new(Magnet::new(options))));
+                self.update_free_space().await;
+            }
+            Action::CreateTorrent => {
+                self.popup = Some(PopUp::CreateTorrent(Box::new(TorrentCreator::new(
+                    self.upload_torrent_files,
+                    self.local_daemon,
+                ))));
+            }
+            Action::ToggleTurtle => self.toggle_alt_speed().await,
+            Action::Statistics => {
+                let stats = self.session_stats().await;
+                let view = StatsView::new(stats, &self.all_torrents.lock().await);
+                self.popup = Some(PopUp::Stats(Box::new(view)));
+            }
+            Action::Settings => {
+                let values = self.session.session_get().await;
+                self.popup = Some(PopUp::Settings(Box::new(Settings::new(values))));
+            }
+            Action::Help => self.show_help(),
+            Action::CommandLine => self.command_line.open(),
+            Action::GrowPane => self.resize_pane(true),
+            Action::ShrinkPane => self.resize_pane(false),
+            Action::ZoomPane => self.zoomed = !self.zoomed,
+            Action::ThemePicker => {
+                let picker = ThemePicker::new(&self.theme_name, self.theme.clone());
+                self.popup = Some(PopUp::ThemePicker(Box::new(picker)));
+            }
+            Action::ToggleLayout => {
+                self.layout.horizontal = !self.layout.horizontal;
+                self.save_layout();
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the split between the panes by a step towards or away from the
+    /// focused pane.
+    fn resize_pane(&mut self, grow: bool) {
+        let split = self.layout.split;
+        self.layout.split = if grow == (self.active_pane == Pane::Top) {
+            (split + SPLIT_STEP).min(PaneLayout::MAX_SPLIT)
+        } else {
+            split.saturating_sub(SPLIT_STEP).max(PaneLayout::MIN_SPLIT)
+        };
+        self.zoomed = false;
+        self.save_layout();
+    }
+
+    fn save_layout(&mut self) {
+        if let Err(e) = save_layout(&self.layout) {
+            self.command_line
+                .set_message(format!("Could not save the layout: {e}"), true);
+        }
+    }
+
+    fn show_help(&mut self) {
+        if let Some(contexts) = self.help_contexts() {
+            self.help = Some(Help::new(&self.keymap, &contexts));
+        }
+    }
+
+    /// Contexts of the focused pane, the pane's own before the global ones.
+    fn pane_contexts(&self) -> Vec<Context> {
         match self.active_pane {
-            Pane::Top => self.handle_top_pane(key).await,
-            Pane::Bottom => self.handle_bottom_pane(key).await,
+            Pane::Top => vec![Context::Torrents, Context::Global],
+            Pane::Bottom => {
+                let mut contexts = vec![Context::Details];
+                if BottomTab::Files == self.bottom_tab.selected_tab().parse().unwrap() {
+                    contexts.push(Context::Files);
+                }
+                contexts.push(Context::Global);
+                contexts
+            }
         }
     }
 
+    /// Help sections for the focused pane or popup, `None` while a popup takes
+    /// text input.
+    fn help_contexts(&self) -> Option<Vec<(&'static str, Context)>> {
+        let contexts = match &self.popup {
+            None => self.pane_contexts(),
+            Some(PopUp::FilePicker) => vec![self.file_picker.key_context()?],
+            Some(PopUp::TorrentAdder(ta)) => vec![ta.key_context()?],
+            Some(PopUp::AddMagnet(am)) => vec![am.key_context()?],
+            Some(PopUp::BatchAdder(ba)) => vec![ba.key_context()?],
+            Some(PopUp::CreateTorrent(tc)) => vec![tc.key_context()?],
+            Some(PopUp::Settings(settings)) => vec![settings.key_context()?],
+            Some(PopUp::Stats(_)) => vec![Context::Stats],
+            Some(PopUp::TorrentAction(_) | PopUp::Duplicate(_) | PopUp::ThemePicker(_)) => {
+                vec![Context::Menu]
+            }
+            Some(PopUp::DeleteConfirmation(_)) => vec![Context::Confirm],
+        };
+        let titled = contexts
+            .into_iter()
+            .map(|context| {
+                let title = match context {
+                    Context::Global => "Global",
+                    Context::Torrents => "Torrent list",
+                    Context::Details => "Bottom pane",
+                    Context::Files => "Files",
+                    Context::Adder => "Add torrent",
+                    Context::Picker => "File picker",
+                    Context::Magnet => "Add magnet link",
+                    Context::Batch => "Add torrents",
+                    Context::Options => "Add options",
+                    Context::Creator => "Create torrent",
+                    Context::Settings => "Settings",
+                    Context::Stats => "Statistics",
+                    Context::Menu => "Menu",
+                    Context::Confirm => "Remove torrent",
+                    Context::Help => "Keys",
+                };
+                (title, context)
+            })
+            .collect();
+        Some(titled)
+    }
+
     async fn handle_torrent_adder(&mut self, key: KeyEvent) {
         if let Some(PopUp::TorrentAdder(ta)) = self.popup.as_mut() {
-            let (close, torrent) = ta.handler(key);
+            let (close, torrent) = ta.handler(key, &self.keymap).await;
+            let hashes: Vec<String> = ta.info_hash.iter().cloned().collect();
+            let trackers = ta.trackers.clone();
 
+            if close {
+                self.popup = None;
+            }
             if let Some(torrent) = torrent
-                && let Err(e) = self.client.lock().await.torrent_add(torrent).await
+                && let Err(e) = self.submit_torrent(torrent, hashes, trackers).await
             {
-                eprintln!("Failed to add torrent: {:?}", e);
-                std::process::exit(1);
+                self.command_line.set_message(e, true);
             }
+        }
+        self.update_free_space().await;
+    }
 
-            if close {
-                self.popup = None;
-            }
+    /// Adds a torrent unless one with the same info-hash is already in the daemon,
+    /// in which case the user picks what to do with the existing one instead.
+    async fn submit_torrent(
+        &mut self,
+        torrent: TorrentAddArgs,
+        hashes: Vec<String>,
+        trackers: Vec<String>,
+    ) -> std::result::Result<(), String> {
+        let existing = self
+            .all_torrents
+            .lock()
+            .await
+            .iter()
+            .find(|t| {
+                t.hash_string
+                    .as_ref()
+                    .is_some_and(|h| hashes.iter().any(|x| x.eq_ignore_ascii_case(h)))
+            })
+            .cloned();
+
+        if let Some(t) = existing
+            && let Some(id) = t.id()
+        {
+            self.popup = Some(PopUp::Duplicate(DuplicatePopup::new(
+                id,
+                t.name.unwrap_or_default(),
+                t.hash_string.unwrap_or_default(),
+                trackers,
+            )));
+            return Ok(());
         }
+
+        rpc_result(self.client.lock().await.torrent_add(torrent).await)
+            .map_err(|e| format!("Failed to add torrent: {e}"))
+    }
+
+    /// Fetches the free space of the download dir chosen in an add popup.
+    async fn update_free_space(&mut self) {
+        let options = match self.popup.as_mut() {
+            Some(PopUp::TorrentAdder(ta)) => &mut ta.options,
+            Some(PopUp::AddMagnet(am)) => &mut am.options,
+            Some(PopUp::BatchAdder(ba)) => &mut ba.options,
+            _ => return,
+        };
+        let Some(dir) = options.free_space_request() else {
+            return;
+        };
+        let bytes = match self.client.lock().await.free_space(dir.clone()).await {
+            Ok(resp) if resp.is_ok() => Some(resp.arguments.size_bytes),
+            _ => None,
+        };
+        options.set_free_space(dir, bytes);
+    }
+
+    /// Add options preset with the daemon's default download dir.
+    async fn add_options(&self) -> AddOptions {
+        let download_dir = match self.client.lock().await.session_get().await {
+            Ok(resp) => resp.arguments.download_dir,
+            Err(_) => String::new(),
+        };
+        AddOptions::new(download_dir, self.local_daemon)
     }
 
     async fn handle_magnet_adder(&mut self, key: KeyEvent) {
         if let Some(PopUp::AddMagnet(am)) = self.popup.as_mut() {
-            let (close, torrent) = am.handler(key);
+            let (close, torrent) = am.handler(key, &self.keymap).await;
+            let hashes = am.info_hashes();
+            let trackers = am.trackers();
 
+            if close {
+                self.popup = None;
+            }
             if let Some(torrent) = torrent
-                && let Err(e) = self.client.lock().await.torrent_add(torrent).await
+                && let Err(e) = self.submit_torrent(torrent, hashes, trackers).await
             {
-                eprintln!("Failed to add torrent: {:?}", e);
-                std::process::exit(1);
+                self.command_line.set_message(e, true);
             }
+        }
+        self.update_free_space().await;
+    }
 
-            if close {
+    async fn handle_duplicate(&mut self, key: KeyEvent) {
+        let Some(PopUp::Duplicate(d)) = self.popup.as_mut() else {
+            return;
+        };
+        match self.keymap.action(Context::Menu, &key) {
+            Some(Action::Close) => self.popup = None,
+            Some(Action::Down) => d.select_next(),
+            Some(Action::Up) => d.select_prev(),
+            Some(Action::Confirm) => {
+                let id = d.id.clone();
+                let hash = d.hash.clone();
+                let trackers = d.trackers.clone();
+                match d.get_selected() {
+                    Some("Jump to existing torrent") => self.jump_to_torrent(&hash).await,
+                    Some("Merge trackers") => {
+                        if let Err(e) = self.merge_trackers(id, &hash, trackers).await {
+                            self.command_line.set_message(e, true);
+                        }
+                    }
+                    _ => {}
+                }
                 self.popup = None;
             }
+            _ => {}
         }
     }
 
+    /// Shows the `All` tab with the torrent of the given hash selected.
+    async fn jump_to_torrent(&mut self, hash: &str) {
+        let known = self
+            .all_torrents
+            .lock()
+            .await
+            .iter()
+            .any(|t| t.hash_string.as_deref() == Some(hash));
+        if !known {
+            return;
+        }
+        self.top_tab.select(0);
+        self.top_table.select_hash(hash.to_string());
+        self.focus_pane(Pane::Top);
+    }
+
+    fn focus_pane(&mut self, pane: Pane) {
+        self.active_pane = pane;
+        self.top_tab.is_focused = pane == Pane::Top;
+        self.bottom_tab.is_focused = pane == Pane::Bottom;
+    }
+
+    /// Appends trackers the torrent doesn't have yet, each in its own tier.
+    async fn merge_trackers(
+        &mut self,
+        id: Id,
+        hash: &str,
+        trackers: Vec<String>,
+    ) -> std::result::Result<(), String> {
+        let current = self
+            .all_torrents
+            .lock()
+            .await
+            .iter()
+            .find(|t| t.hash_string.as_deref() == Some(hash))
+            .and_then(|t| t.tracker_list.clone());
+
+        let tsa = match current {
+            Some(list) => {
+                let mut lines: Vec<String> = list.trim_end().lines().map(str::to_string).collect();
+                let new: Vec<String> = trackers
+                    .into_iter()
+                    .filter(|t| !lines.contains(t))
+                    .collect();
+                if new.is_empty() {
+                    return Ok(());
+                }
+                for tracker in new {
+                    if !lines.is_empty() {
+                        lines.push(String::new());
+                    }
+                    lines.push(tracker);
+                }
+                TorrentSetArgs::new().tracker_list(TrackerList(lines))
+            }
+            None => TorrentSetArgs::new().tracker_add(trackers),
+        };
+
+        rpc_result(
+            self.client
+                .lock()
+                .await
+                .torrent_set(tsa, Some(vec![id]))
+                .await,
+        )
+        .map_err(|e| format!("Failed to merge trackers: {e}"))
+    }
+
     async fn handle_popup_delete(&mut self, key: KeyEvent) {
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('y'), _) | (KeyCode::Char('Y'), _) => {
+        match self.keymap.action(Context::Confirm, &key) {
+            Some(Action::Confirm) => {
                 if let PopUp::DeleteConfirmation(p) = self.popup.as_ref().unwrap() {
                     self.delete_torrent(p.id.clone(), p.with_data).await;
                     self.popup = None;
                 }
             }
-            (KeyCode::Char('n'), _) | (KeyCode::Char('N'), _) | (KeyCode::Char('q'), _) => {
-                self.popup = None
-            }
-            (KeyCode::Char('['), m) if m.contains(KeyModifiers::CONTROL) => self.popup = None,
+            Some(Action::Close) => self.popup = None,
             _ => {}
         }
     }
 
     async fn handle_actions_menu(&mut self, key: KeyEvent) {
-        if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
-            self.popup = None;
-            return;
-        }
-        if matches!((key.code, key.modifiers), (KeyCode::Char('['), m) if m.contains(KeyModifiers::CONTROL))
-        {
-            self.popup = None;
-            return;
+        if let Some(PopUp::TorrentAction(a)) = self.popup.as_mut() {
+            match self.keymap.action(Context::Menu, &key) {
+                Some(Action::Close) => self.popup = None,
+                Some(Action::Down) => a.select_next(),
+                Some(Action::Up) => a.select_prev(),
+                Some(Action::Confirm) => self.run_menu_action().await,
+                _ => {}
+            }
         }
+    }
 
-        let action = if let Some(PopUp::TorrentAction(a)) = self.popup.as_mut() {
-            match key.code {
-                KeyCode::Char('j') | KeyCode::Down => {
-                    a.select_next();
-                    return;
-                }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    a.select_prev();
-                    return;
-                }
-                KeyCode::Enter => match a.get_selected() {
-                    Some(s) => Some((s, a.id.clone())),
-                    None => None,
-                },
-                _ => None,
-            }
-        } else {
-            None
+    /// Runs the entry selected in the action menu and closes it.
+    async fn run_menu_action(&mut self) {
+        let action = match self.popup.as_ref() {
+            Some(PopUp::TorrentAction(a)) => a.get_selected().map(|s| (s, a.id.clone())),
+            _ => None,
         };
 
         if let Some((selected, id)) = action {
@@ -376,83 +944,262 @@ impl App {
     }
 
     async fn handle_filepicker(&mut self, key: KeyEvent) {
-        let (close, path) = self.file_picker.handler(key).await;
+        let (close, paths) = self.file_picker.handler(key, &self.keymap).await;
+        self.picked_torrents(close, paths).await;
+    }
+
+    /// Opens the adder for one picked torrent, the batch adder for several.
+    async fn picked_torrents(&mut self, close: bool, mut paths: Vec<String>) {
         if close {
             self.popup = None;
         }
-        if let Some(path) = path {
-            self.popup = Some(PopUp::TorrentAdder(TorrentAdder::new(path)));
+        if !paths.is_empty() {
+            // The picker directory is kept even if the app doesn't exit cleanly.
+            self.save_ui_state();
+        }
+        if paths.len() == 1 {
+            let options = self.add_options().await;
+            self.popup = Some(PopUp::TorrentAdder(Box::new(TorrentAdder::new(
+                paths.remove(0),
+                options,
+                self.upload_torrent_files,
+            ))));
+            self.update_free_space().await;
+        } else if !paths.is_empty() {
+            let options = self.add_options().await;
+            let known: Vec<String> = self
+                .all_torrents
+                .lock()
+                .await
+                .iter()
+                .filter_map(|t| t.hash_string.clone())
+                .collect();
+            self.popup = Some(PopUp::BatchAdder(Box::new(BatchAdder::new(
+                paths,
+                options,
+                self.upload_torrent_files,
+                &known,
+            ))));
+            self.update_free_space().await;
+        }
+    }
+
+    async fn update_status_bar(&mut self) {
+        let session = self.session_status.lock().await.clone();
+        let torrents = self.all_torrents.lock().await;
+        self.status_bar.set_data(&torrents, session);
+
+        let mut view = vec![];
+        if let Some(sort) = self.sort {
+            view.push(format!("sort: {sort}"));
+        }
+        if !self.filter.is_empty() {
+            view.push(format!("filter: {}", self.filter));
+        }
+        self.status_bar.set_view(view.join("  "));
+    }
+
+    /// Flips turtle mode, the status bar shows it right away.
+    async fn toggle_alt_speed(&mut self) {
+        let Some(enabled) = self.status_bar.alt_speed_enabled() else {
+            return;
+        };
+        let args = SessionSetArgs {
+            alt_speed_enabled: Some(!enabled),
+            ..Default::default()
+        };
+        if let Ok(resp) = self.client.lock().await.session_set(args).await
+            && resp.is_ok()
+        {
+            self.session_status.lock().await.alt_speed_enabled = Some(!enabled);
+        }
+    }
+
+    async fn session_stats(&self) -> Result<SessionStats, String> {
+        match self.client.lock().await.session_stats().await {
+            Ok(resp) if resp.is_ok() => Ok(resp.arguments),
+            Ok(resp) => Err(resp.result),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    async fn handle_stats(&mut self, key: KeyEvent) {
+        let Some(PopUp::Stats(view)) = self.popup.as_mut() else {
+            return;
+        };
+        let (close, refresh) = view.handler(key, &self.keymap);
+        if close {
+            self.popup = None;
+            return;
+        }
+        if refresh {
+            let stats = self.session_stats().await;
+            if let Some(PopUp::Stats(view)) = self.popup.as_mut() {
+                view.set_data(stats, &self.all_torrents.lock().await);
+            }
+        }
+    }
+
+    /// Moving through the list previews each theme, Esc puts the old one back.
+    fn handle_theme_picker(&mut self, key: KeyEvent) {
+        let Some(PopUp::ThemePicker(picker)) = self.popup.as_mut() else {
+            return;
+        };
+        match self.keymap.action(Context::Menu, &key) {
+            Some(Action::Down) => picker.select_next(),
+            Some(Action::Up) => picker.select_prev(),
+            Some(Action::Confirm) => {
+                // The highlighted theme is already shown when it loaded.
+                match picker.theme() {
+                    Ok(_) => {
+                        self.theme_name = picker.selected().to_string();
+                        self.popup = None;
+                    }
+                    Err(e) => self.command_line.set_message(e.clone(), true),
+                }
+                return;
+            }
+            Some(Action::Close) => {
+                self.theme = picker.original.clone();
+                self.popup = None;
+                return;
+            }
+            _ => return,
+        }
+        match picker.theme() {
+            Ok(theme) => self.theme = theme.clone(),
+            Err(e) => self.command_line.set_message(e.clone(), true),
+        }
+    }
+
+    async fn handle_settings(&mut self, key: KeyEvent) {
+        let Some(PopUp::Settings(settings)) = self.popup.as_mut() else {
+            return;
+        };
+        let (close, args) = settings.handler(key, &self.keymap);
+        if let Some(args) = args {
+            let result = match self.client.lock().await.session_set(args).await {
+                Ok(resp) if resp.is_ok() => Ok(()),
+                Ok(resp) => Err(resp.result),
+                Err(e) => Err(e.to_string()),
+            };
+            settings.save_result(result);
+        }
+        if close {
+            self.popup = None;
         }
     }
 
+    async fn handle_torrent_creator(&mut self, key: KeyEvent) {
+        if let Some(PopUp::CreateTorrent(tc)) = self.popup.as_mut()
+            && tc.handler(key, &self.keymap).await
+        {
+            self.popup = None;
+        }
+    }
+
+    /// Writes a finished torrent from the create popup and adds it for seeding.
+    async fn poll_torrent_creator(&mut self) {
+        let Some(PopUp::CreateTorrent(tc)) = self.popup.as_mut() else {
+            return;
+        };
+        if let Some(args) = tc.poll() {
+            let hashes = tc.info_hash.iter().cloned().collect();
+            let trackers = tc.tracker_list();
+            if let Err(e) = self.submit_torrent(args, hashes, trackers).await {
+                self.command_line.set_message(e, true);
+            }
+        }
+    }
+
+    /// Shows the latest error of the watch dirs, older ones are dropped.
+    async fn show_watch_errors(&mut self) {
+        let mut errors = self.watch_errors.lock().await;
+        if let Some(e) = errors.pop() {
+            errors.clear();
+            self.command_line.set_message(e, true);
+        }
+    }
+
+    async fn handle_batch_adder(&mut self, key: KeyEvent) {
+        if let Some(PopUp::BatchAdder(ba)) = self.popup.as_mut() {
+            let (close, items) = ba.handler(key, &self.keymap).await;
+            if close {
+                self.popup = None;
+            }
+            // Keep adding the rest when one fails, the last error is shown.
+            for item in items {
+                if let Err(e) = self
+                    .submit_torrent(item.args, item.hashes, item.trackers)
+                    .await
+                {
+                    self.command_line.set_message(e, true);
+                }
+            }
+        }
+        self.update_free_space().await;
+    }
+
     async fn handle_top_pane(&mut self, key: KeyEvent) {
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('j'), m) if m.contains(KeyModifiers::CONTROL) => {
-                self.active_pane = Pane::Bottom;
-                self.top_tab.is_focused = false;
-                self.bottom_tab.is_focused = true;
-            }
-            (KeyCode::Char('K'), _) => {
-                let index = self.top_table.state.selected();
-                if index.is_none() {
+        if let Some(action) = self.keymap.action(Context::Torrents, &key) {
+            self.top_pane_action(action).await;
+        }
+    }
+
+    async fn top_pane_action(&mut self, action: Action) {
+        match action {
+            Action::FocusBottom => self.focus_pane(Pane::Bottom),
+            Action::OpenMenu => {
+                let Some(t) = self.top_table.selected() else {
                     return;
-                }
-                let t = self.top_table.torrents.get(index.unwrap()).unwrap();
+                };
                 let id = t.id().unwrap();
                 let name = t.name.clone().unwrap();
                 let popup = PopUp::TorrentAction(TorrentActions::new(id, name));
                 self.popup = Some(popup);
             }
-            (KeyCode::Char('D'), _) => {
-                let index = self.top_table.state.selected();
-                if index.is_none() {
+            Action::DeleteWithData => {
+                let Some(t) = self.top_table.selected() else {
                     return;
-                }
-                let t = self.top_table.torrents.get(index.unwrap()).unwrap();
+                };
                 let id = t.id().unwrap();
                 let name = t.name.clone().unwrap();
                 let popup = PopUp::DeleteConfirmation(DeletePopup::new(id, name, true));
                 self.popup = Some(popup);
             }
-            (KeyCode::Char('d'), _) => {
-                let index = self.top_table.state.selected();
-                if index.is_none() {
+            Action::Delete => {
+                let Some(t) = self.top_table.selected() else {
                     return;
-                }
-                let t = self.top_table.torrents.get(index.unwrap()).unwrap();
+                };
                 let id = t.id().unwrap();
                 let name = t.name.clone().unwrap();
                 let popup = PopUp::DeleteConfirmation(DeletePopup::new(id, name, false));
                 self.popup = Some(popup);
             }
-            (KeyCode::Char('p'), _) => {
-                let index = self.top_table.state.selected();
-                if index.is_none() {
+            Action::Pause => {
+                let Some(t) = self.top_table.selected() else {
                     return;
-                }
-                let t = self.top_table.torrents.get(index.unwrap()).unwrap();
+                };
                 let id = t.id().unwrap();
                 self.pause(id).await;
             }
-            (KeyCode::Char('r'), _) => {
-                let index = self.top_table.state.selected();
-                if index.is_none() {
+            Action::Resume => {
+                let Some(t) = self.top_table.selected() else {
                     return;
-                }
-                let t = self.top_table.torrents.get(index.unwrap()).unwrap();
+                };
                 let id = t.id().unwrap();
                 self.resume(id).await;
             }
-            (KeyCode::Char('j'), _) | (KeyCode::Down, _) => {
+            Action::Down => {
                 self.top_table.select_next();
             }
-            (KeyCode::Char('k'), _) | (KeyCode::Up, _) => {
+            Action::Up => {
                 self.top_table.select_prev();
             }
-            (KeyCode::Char('l'), _) | (KeyCode::Right, _) => {
+            Action::NextTab => {
                 self.top_tab.select_next();
             }
-            (KeyCode::Char('h'), _) | (KeyCode::Left, _) => {
+            Action::PrevTab => {
                 self.top_tab.select_prev();
             }
             _ => {}
@@ -460,32 +1207,38 @@ impl App {
     }
 
     async fn handle_bottom_pane(&mut self, key: KeyEvent) {
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('k'), m) if m.contains(KeyModifiers::CONTROL) => {
-                self.active_pane = Pane::Top;
-                self.bottom_tab.is_focused = false;
-                self.top_tab.is_focused = true;
+        if let Some(action) = self.keymap.action(Context::Details, &key) {
+            self.bottom_pane_action(Context::Details, action).await;
+        } else if BottomTab::Files == self.bottom_tab.selected_tab().parse().unwrap()
+            && let Some(action) = self.keymap.action(Context::Files, &key)
+        {
+            self.bottom_pane_action(Context::Files, action).await;
+        }
+    }
+
+    async fn bottom_pane_action(&mut self, context: Context, action: Action) {
+        match (context, action) {
+            (Context::Details, Action::FocusTop) => {
+                self.focus_pane(Pane::Top);
                 return;
             }
-            (KeyCode::Char('l'), _) | (KeyCode::Right, _) => {
+            (Context::Details, Action::NextTab) => {
                 self.bottom_tab.select_next();
                 return;
             }
-            (KeyCode::Char('h'), _) | (KeyCode::Left, _) => {
+            (Context::Details, Action::PrevTab) => {
                 self.bottom_tab.select_prev();
                 return;
             }
             _ => {}
         }
 
-        if BottomTab::Files == self.bottom_tab.selected_tab().parse().unwrap()
-            && let Some(tsa) = self.bottom_pane.files_table.handler(key)
+        if context == Context::Files
+            && let Some(tsa) = self.bottom_pane.files_table.handler(action)
         {
-            let index = self.top_table.state.selected();
-            if index.is_none() {
+            let Some(t) = self.top_table.selected() else {
                 return;
-            }
-            let t = self.top_table.torrents.get(index.unwrap()).unwrap();
+            };
             let id = t.id().unwrap();
             let client = self.client.clone();
             tokio::spawn(async move {
@@ -494,9 +1247,146 @@ impl App {
         }
     }
 
+    async fn run_command(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let result = match Command::parse(line, &self.pane_contexts()) {
+            Ok(command) => self.execute(command).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(Some(message)) => self.command_line.set_message(message, false),
+            Ok(None) => {}
+            Err(e) => self.command_line.set_message(e, true),
+        }
+    }
+
+    /// Runs a command line, returns a message to show in its place.
+    async fn execute(&mut self, command: Command) -> std::result::Result<Option<String>, String> {
+        match command {
+            Command::Action(Context::Global, action) => self.global_action(action).await,
+            Command::Action(Context::Torrents, action) => self.top_pane_action(action).await,
+            Command::Action(context, action) => self.bottom_pane_action(context, action).await,
+            Command::Pause(target) => {
+                let ids = self.target_ids(target).await?;
+                rpc_result(
+                    self.client
+                        .lock()
+                        .await
+                        .torrent_action(TorrentAction::Stop, ids)
+                        .await,
+                )?;
+            }
+            Command::Resume(target) => {
+                let ids = self.target_ids(target).await?;
+                rpc_result(
+                    self.client
+                        .lock()
+                        .await
+                        .torrent_action(TorrentAction::Start, ids)
+                        .await,
+                )?;
+            }
+            Command::Sort(sort) => {
+                self.sort = sort;
+                return Ok(Some(match sort {
+                    Some(sort) => format!("Sorted by {sort}"),
+                    None => "Sorted in daemon order".to_string(),
+                }));
+            }
+            Command::Limit { upload, speed } => {
+                let args = if upload {
+                    SessionSetArgs {
+                        speed_limit_up_enabled: Some(speed.is_some()),
+                        speed_limit_up: speed,
+                        ..Default::default()
+                    }
+                } else {
+                    SessionSetArgs {
+                        speed_limit_down_enabled: Some(speed.is_some()),
+                        speed_limit_down: speed,
+                        ..Default::default()
+                    }
+                };
+                rpc_result(self.client.lock().await.session_set(args).await)?;
+                let direction = if upload { "Upload" } else { "Download" };
+                return Ok(Some(match speed {
+                    Some(kb) => format!("{direction} limit set to {kb} KB/s"),
+                    None => format!("{direction} limit off"),
+                }));
+            }
+            Command::Move(dir) => {
+                let ids = self.target_ids(Target::Selected).await?;
+                let location = expand_path(&dir).to_string_lossy().to_string();
+                rpc_result(
+                    self.client
+                        .lock()
+                        .await
+                        .torrent_set_location(ids, location.clone(), Some(true))
+                        .await,
+                )?;
+                return Ok(Some(format!("Moving to {location}")));
+            }
+            Command::Label(op) => {
+                let (id, mut labels) = self
+                    .selected_torrent()
+                    .and_then(|t| Some((t.id()?, t.labels.clone().unwrap_or_default())))
+                    .ok_or("no torrent selected")?;
+                match op {
+                    LabelOp::Add(new) => {
+                        for label in new {
+                            if !labels.contains(&label) {
+                                labels.push(label);
+                            }
+                        }
+                    }
+                    LabelOp::Remove(old) => labels.retain(|l| !old.contains(l)),
+                    LabelOp::Set(new) => labels = new,
+                }
+                let mut args = TorrentSetArgs::new();
+                args.labels = Some(labels);
+                rpc_result(
+                    self.client
+                        .lock()
+                        .await
+                        .torrent_set(args, Some(vec![id]))
+                        .await,
+                )?;
+            }
+            Command::Theme(name) => {
+                self.theme = resolve_theme(&name)?;
+                self.theme_name = name;
+            }
+            Command::Filter(filter) => self.filter = filter,
+        }
+        Ok(None)
+    }
+
+    fn selected_torrent(&self) -> Option<&Torrent> {
+        self.top_table.selected()
+    }
+
+    async fn target_ids(&self, target: Target) -> std::result::Result<Vec<Id>, String> {
+        match target {
+            Target::Selected => self
+                .selected_torrent()
+                .and_then(|t| t.id())
+                .map(|id| vec![id])
+                .ok_or_else(|| "no torrent selected".to_string()),
+            Target::All => Ok(self
+                .all_torrents
+                .lock()
+                .await
+                .iter()
+                .filter_map(|t| t.id())
+                .collect()),
+        }
+    }
+
     async fn filter_torrents(&mut self) {
         let all_torrents = self.all_torrents.lock().await;
-        let filtered_torrents: Vec<_> = match self.top_tab.selected_tab().parse().unwrap() {
+        let mut filtered_torrents: Vec<_> = match self.top_tab.selected_tab().parse().unwrap() {
             TopTab::All => all_torrents.clone(),
             TopTab::Completed => all_torrents
                 .iter()
@@ -514,29 +1404,26 @@ impl App {
                 .cloned()
                 .collect(),
         };
-        self.top_table.torrents = filtered_torrents;
+        filtered_torrents.retain(|t| self.filter.matches(t));
+        if let Some(sort) = self.sort {
+            sort.apply(&mut filtered_torrents);
+        }
+        self.top_table.set_torrents(filtered_torrents);
     }
 
     async fn set_data_bottom_pane(&mut self) {
-        if self.top_table.state.selected().is_none() {
+        let Some(sel_tor) = self.top_table.selected() else {
             self.clear_bottom_pane_data();
             return;
         };
 
-        let selected_index = self.top_table.state.selected().unwrap();
-        if selected_index >= self.top_table.torrents.len() {
-            self.clear_bottom_pane_data();
-            return;
-        }
-
-        let sel_tor = self.top_table.torrents.get(selected_index).unwrap();
-
         match self.bottom_tab.selected_tab().parse().unwrap() {
             BottomTab::Files => {
-                let files = sel_tor.files.clone().unwrap();
-                self.bottom_pane.files_table.files = files;
-                self.bottom_pane.files_table.priorities = sel_tor.priorities.clone().unwrap();
-                self.bottom_pane.files_table.wanted = sel_tor.wanted.clone().unwrap();
+                self.bottom_pane.files_table.set_data(
+                    sel_tor.files.clone().unwrap(),
+                    sel_tor.priorities.clone().unwrap(),
+                    sel_tor.wanted.clone().unwrap(),
+                );
             }
             BottomTab::Peers => {
                 let peers = sel_tor.peers.clone().unwrap();
@@ -551,9 +1438,7 @@ impl App {
     fn clear_bottom_pane_data(&mut self) {
         match self.bottom_tab.selected_tab().parse().unwrap() {
             BottomTab::Files => {
-                self.bottom_pane.files_table.files.clear();
-                self.bottom_pane.files_table.priorities.clear();
-                self.bottom_pane.files_table.wanted.clear();
+                self.bottom_pane.files_table.clear();
             }
             BottomTab::Peers => {
                 self.bottom_pane.peers_table.peers.clear();
@@ -578,3 +1463,21 @@ impl App {
         let _ = client.torrent_remove(vec![id], with_data).await;
     }
 }
+
+/// Tabs on top, the pane's content below.
+fn split_tabs(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Length(3), Min(0)])
+        .split(area)
+}
+
+fn rpc_result<T: RpcResponseArgument, E: ToString>(
+    response: std::result::Result<RpcResponse<T>, E>,
+) -> std::result::Result<(), String> {
+    match response {
+        Ok(resp) if resp.is_ok() => Ok(()),
+        Ok(resp) => Err(resp.result),
+        Err(e) => Err(e.to_string()),
+    }
+}
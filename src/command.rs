@@ -0,0 +1,426 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use transmission_rpc::types::{ErrorType, Torrent, TorrentStatus};
+
+use crate::config::theme_names;
+use crate::keymap::{Action, Context, action_names, find_action};
+use crate::util::complete_path;
+
+const COMMANDS: [&str; 8] = [
+    "pause", "resume", "sort", "limit", "move", "label", "theme", "filter",
+];
+
+const STATUSES: [&str; 8] = [
+    "downloading",
+    "seeding",
+    "stopped",
+    "checking",
+    "queued",
+    "active",
+    "complete",
+    "error",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Selected,
+    All,
+}
+
+#[derive(Debug, Clone)]
+pub enum LabelOp {
+    Add(Vec<String>),
+    Remove(Vec<String>),
+    Set(Vec<String>),
+}
+
+/// A parsed command line.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// A keymap action run by its config name.
+    Action(Context, Action),
+    Pause(Target),
+    Resume(Target),
+    /// `None` restores the daemon's order.
+    Sort(Option<Sort>),
+    /// Session speed limit in KB/s, `None` turns it off.
+    Limit {
+        upload: bool,
+        speed: Option<i32>,
+    },
+    Move(String),
+    Label(LabelOp),
+    Theme(String),
+    Filter(Filter),
+}
+
+impl Command {
+    /// Keymap actions are looked up in `contexts` in order, so the focused
+    /// pane wins over global ones with the same name.
+    pub fn parse(line: &str, contexts: &[Context]) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+
+        match name {
+            "pause" | "resume" => {
+                let target = match args.as_slice() {
+                    [] => Target::Selected,
+                    ["all"] => Target::All,
+                    _ => return Err(format!("usage: {name} [all]")),
+                };
+                Ok(if name == "pause" {
+                    Command::Pause(target)
+                } else {
+                    Command::Resume(target)
+                })
+            }
+            "sort" if args.is_empty() => Ok(Command::Sort(None)),
+            "sort" => Ok(Command::Sort(Some(args.join(" ").parse()?))),
+            "limit" => match args.as_slice() {
+                [direction @ ("down" | "up"), value] => Ok(Command::Limit {
+                    upload: *direction == "up",
+                    speed: match *value {
+                        "off" => None,
+                        value => Some(parse_speed(value)?),
+                    },
+                }),
+                _ => Err("usage: limit down|up <speed>|off".to_string()),
+            },
+            "move" if !args.is_empty() => Ok(Command::Move(args.join(" "))),
+            "move" => Err("usage: move <dir>".to_string()),
+            "label" => {
+                let labels = |rest: &[&str]| rest.iter().map(|l| l.to_string()).collect();
+                match args.as_slice() {
+                    ["add", rest @ ..] if !rest.is_empty() => {
+                        Ok(Command::Label(LabelOp::Add(labels(rest))))
+                    }
+                    ["remove", rest @ ..] if !rest.is_empty() => {
+                        Ok(Command::Label(LabelOp::Remove(labels(rest))))
+                    }
+                    ["set", rest @ ..] => Ok(Command::Label(LabelOp::Set(labels(rest)))),
+                    ["clear"] => Ok(Command::Label(LabelOp::Set(vec![]))),
+                    _ => Err("usage: label add|remove|set <labels>, label clear".to_string()),
+                }
+            }
+            "theme" => match args.as_slice() {
+                [theme] => Ok(Command::Theme(theme.to_string())),
+                _ => Err("usage: theme <name>".to_string()),
+            },
+            "filter" => Ok(Command::Filter(args.join(" ").parse()?)),
+            name => match find_action(contexts, name) {
+                Some(_) if !args.is_empty() => Err(format!("{name} takes no arguments")),
+                Some((context, action)) => Ok(Command::Action(context, action)),
+                None => Err(format!("unknown command: {name}")),
+            },
+        }
+    }
+}
+
+/// Full lines the last word of `line` can be completed to.
+pub fn complete(line: &str, contexts: &[Context]) -> Vec<String> {
+    let start = line.rfind(' ').map_or(0, |i| i + 1);
+    let (head, word) = line.split_at(start);
+    let words: Vec<&str> = head.split_whitespace().collect();
+
+    let candidates: Vec<String> = match words.as_slice() {
+        [] => {
+            let mut names: Vec<&str> = COMMANDS.into_iter().chain(action_names(contexts)).collect();
+            names.sort_unstable();
+            names.dedup();
+            names.into_iter().map(|n| format!("{n} ")).collect()
+        }
+        ["pause" | "resume"] => vec!["all".to_string()],
+        ["sort"] => SortKey::ALL
+            .iter()
+            .map(|k| format!("{} ", k.name()))
+            .collect(),
+        ["sort", _] => vec!["asc".to_string(), "desc".to_string()],
+        ["limit"] => vec!["down ".to_string(), "up ".to_string()],
+        ["limit", _] => vec!["off".to_string()],
+        ["label"] => ["add ", "remove ", "set ", "clear"]
+            .map(str::to_string)
+            .to_vec(),
+        ["theme"] => theme_names(),
+        ["filter", ..] => match word.strip_prefix("status:") {
+            Some(_) => STATUSES.map(|s| format!("status:{s} ")).to_vec(),
+            None => ["status:", "label:", "tracker:"]
+                .map(str::to_string)
+                .to_vec(),
+        },
+        // Paths can have spaces, complete everything after the command.
+        ["move", ..] => {
+            let path = line.trim_start()["move".len()..].trim_start();
+            let head = &line[..line.len() - path.len()];
+            return vec![format!("{head}{}", complete_path(path))];
+        }
+        _ => vec![],
+    };
+
+    candidates
+        .into_iter()
+        .filter(|c| c.starts_with(word))
+        .map(|c| format!("{head}{c}"))
+        .collect()
+}
+
+/// `2M`, `500K` or `800` (KB/s) to KB/s.
+fn parse_speed(value: &str) -> Result<i32, String> {
+    let lower = value.to_ascii_lowercase();
+    let lower = lower.trim_end_matches("/s").trim_end_matches('b');
+    let (number, factor) = match lower.char_indices().last() {
+        Some((i, 'k')) => (&lower[..i], 1.0),
+        Some((i, 'm')) => (&lower[..i], 1024.0),
+        Some((i, 'g')) => (&lower[..i], 1024.0 * 1024.0),
+        _ => (lower, 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(n) if n >= 0.0 => Ok((n * factor).round() as i32),
+        _ => Err(format!("invalid speed: {value}")),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Status,
+    Progress,
+    Size,
+    Ratio,
+    Down,
+    Up,
+    Eta,
+    Added,
+    Queue,
+}
+
+impl SortKey {
+    const ALL: [SortKey; 10] = [
+        SortKey::Name,
+        SortKey::Status,
+        SortKey::Progress,
+        SortKey::Size,
+        SortKey::Ratio,
+        SortKey::Down,
+        SortKey::Up,
+        SortKey::Eta,
+        SortKey::Added,
+        SortKey::Queue,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Status => "status",
+            SortKey::Progress => "progress",
+            SortKey::Size => "size",
+            SortKey::Ratio => "ratio",
+            SortKey::Down => "down",
+            SortKey::Up => "up",
+            SortKey::Eta => "eta",
+            SortKey::Added => "added",
+            SortKey::Queue => "queue",
+        }
+    }
+
+    fn compare(self, a: &Torrent, b: &Torrent) -> Ordering {
+        match self {
+            SortKey::Name => {
+                let name = |t: &Torrent| t.name.as_deref().unwrap_or_default().to_lowercase();
+                name(a).cmp(&name(b))
+            }
+            SortKey::Status => a.status.cmp(&b.status),
+            SortKey::Progress => a
+                .percent_done
+                .unwrap_or(0.0)
+                .total_cmp(&b.percent_done.unwrap_or(0.0)),
+            SortKey::Size => a.total_size.cmp(&b.total_size),
+            SortKey::Ratio => a
+                .upload_ratio
+                .unwrap_or(0.0)
+                .total_cmp(&b.upload_ratio.unwrap_or(0.0)),
+            SortKey::Down => a.rate_download.cmp(&b.rate_download),
+            SortKey::Up => a.rate_upload.cmp(&b.rate_upload),
+            // Unknown ETAs are negative, they go last.
+            SortKey::Eta => {
+                let eta = |t: &Torrent| t.eta.filter(|e| *e >= 0).unwrap_or(i64::MAX);
+                eta(a).cmp(&eta(b))
+            }
+            SortKey::Added => a.added_date.cmp(&b.added_date),
+            SortKey::Queue => a.queue_position.cmp(&b.queue_position),
+        }
+    }
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SortKey::ALL
+            .into_iter()
+            .find(|k| k.name() == s)
+            .ok_or_else(|| format!("unknown sort column: {s}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl Sort {
+    /// Numbers sort largest first unless the order is given.
+    fn new(key: SortKey, descending: Option<bool>) -> Self {
+        let descending = descending.unwrap_or(!matches!(
+            key,
+            SortKey::Name | SortKey::Status | SortKey::Eta | SortKey::Queue
+        ));
+        Self { key, descending }
+    }
+
+    pub fn apply(&self, torrents: &mut [Torrent]) {
+        torrents.sort_by(|a, b| {
+            let order = self.key.compare(a, b);
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+}
+
+/// `ratio`, `ratio asc` or `ratio desc`, the same as `:sort` takes.
+impl std::str::FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [key] => Ok(Sort::new(key.parse()?, None)),
+            [key, "asc"] => Ok(Sort::new(key.parse()?, Some(false))),
+            [key, "desc"] => Ok(Sort::new(key.parse()?, Some(true))),
+            _ => Err("usage: sort [column] [asc|desc]".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let order = if self.descending { "desc" } else { "asc" };
+        write!(f, "{} {order}", self.key.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Status(&'static str),
+    Label(String),
+    Tracker(String),
+    Name(String),
+}
+
+/// Terms of `:filter`, a torrent has to match all of them.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    terms: Vec<Term>,
+    text: String,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, t: &Torrent) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Status(status) => status_matches(status, t),
+            Term::Label(label) => t
+                .labels
+                .iter()
+                .flatten()
+                .any(|l| l.eq_ignore_ascii_case(label)),
+            Term::Tracker(tracker) => t
+                .trackers
+                .iter()
+                .flatten()
+                .any(|tr| tr.announce.to_lowercase().contains(tracker)),
+            Term::Name(name) => t
+                .name
+                .as_deref()
+                .is_some_and(|n| n.to_lowercase().contains(name)),
+        })
+    }
+}
+
+fn status_matches(status: &str, t: &Torrent) -> bool {
+    use TorrentStatus::*;
+    match status {
+        "downloading" => t.status == Some(Downloading),
+        "seeding" => t.status == Some(Seeding),
+        "stopped" => t.status == Some(Stopped),
+        "checking" => matches!(t.status, Some(Verifying | QueuedToVerify)),
+        "queued" => matches!(t.status, Some(QueuedToDownload | QueuedToSeed)),
+        "active" => t.rate_download.unwrap_or(0) > 0 || t.rate_upload.unwrap_or(0) > 0,
+        "complete" => t.percent_done == Some(1.0),
+        "error" => t.error.is_some_and(|e| e != ErrorType::Ok),
+        _ => false,
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = s
+            .split_whitespace()
+            .map(|word| {
+                let term = match word.split_once(':') {
+                    Some(("status", value)) => match STATUSES.iter().find(|s| **s == value) {
+                        Some(status) => Term::Status(status),
+                        None => return Err(format!("unknown status: {value}")),
+                    },
+                    Some(("label", value)) => Term::Label(value.to_string()),
+                    Some(("tracker", value)) => Term::Tracker(value.to_lowercase()),
+                    _ => Term::Name(word.to_lowercase()),
+                };
+                Ok(term)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            terms,
+            text: s.split_whitespace().collect::<Vec<_>>().join(" "),
+        })
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_units() {
+        assert_eq!(parse_speed("800"), Ok(800));
+        assert_eq!(parse_speed("500K"), Ok(500));
+        assert_eq!(parse_speed("500kb/s"), Ok(500));
+        assert_eq!(parse_speed("2M"), Ok(2048));
+        assert_eq!(parse_speed("1.5m"), Ok(1536));
+        assert_eq!(parse_speed("1G"), Ok(1024 * 1024));
+        assert_eq!(parse_speed("0"), Ok(0));
+    }
+
+    #[test]
+    fn invalid_speeds() {
+        assert!(parse_speed("").is_err());
+        assert!(parse_speed("-5").is_err());
+        assert!(parse_speed("fast").is_err());
+        assert!(parse_speed("2T").is_err());
+    }
+}
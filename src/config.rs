@@ -1,14 +1,51 @@
 use std::fs;
+use std::path::PathBuf;
 
 use ratatui::style::Color;
 use serde::Deserialize;
 
+use crate::keymap::{Keymap, RawKeymap};
 use crate::util::get_conf_dir;
+use crate::util::is_local_url;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub rpc_config: RpcConfig,
     pub theme: Theme,
+    pub theme_name: String,
+    pub geoip_db: Option<String>,
+    /// Send `.torrent` contents instead of a path the daemon has to read itself.
+    pub upload_torrent_files: bool,
+    /// The daemon runs on this machine, so our paths are valid for it.
+    pub local_daemon: bool,
+    pub watch_dirs: Vec<WatchDir>,
+    /// Directories the file picker can jump to.
+    pub bookmarks: Vec<String>,
+    /// Capture the mouse for clicking and scrolling.
+    pub mouse: bool,
+    pub layout: PaneLayout,
+    #[serde(skip)]
+    pub keys: Keymap,
+}
+
+/// A directory scanned for new `.torrent` files, with the options used to add them.
+#[derive(Debug, Deserialize, Clone)]
+pub struct WatchDir {
+    pub path: String,
+    #[serde(default)]
+    pub download_dir: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Where processed files are moved, they are renamed to `*.added` when unset.
+    #[serde(default)]
+    pub move_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+enum RawWatchDir {
+    Path(String),
+    Dir(WatchDir),
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -17,6 +54,44 @@ struct RawConfig {
     pub username: String,
     pub password: String,
     pub theme: String,
+    #[serde(default)]
+    pub geoip_db: Option<String>,
+    #[serde(default)]
+    pub upload_torrent_files: Option<bool>,
+    #[serde(default)]
+    pub watch_dirs: Vec<RawWatchDir>,
+    #[serde(default)]
+    pub bookmarks: Vec<String>,
+    #[serde(default)]
+    pub mouse: Option<bool>,
+    #[serde(default)]
+    pub layout: PaneLayout,
+    #[serde(default)]
+    pub keys: RawKeymap,
+}
+
+/// How the torrent list and the bottom pane share the screen.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct PaneLayout {
+    /// Percent of the space given to the torrent list.
+    pub split: u16,
+    /// Torrent list and bottom pane side by side instead of stacked.
+    pub horizontal: bool,
+}
+
+impl PaneLayout {
+    pub const MIN_SPLIT: u16 = 20;
+    pub const MAX_SPLIT: u16 = 80;
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self {
+            split: 50,
+            horizontal: false,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -71,42 +146,307 @@ pub struct Details {
 }
 
 impl Theme {
+    /// Colors are checked when a theme is loaded, the white fallback is only
+    /// reached by strings that never went through `resolve_theme`.
     pub fn color(s: &str) -> Color {
-        let s = s.trim_start_matches('#').trim_start_matches("0x");
-        if let Ok(rgb) = u32::from_str_radix(s, 16) {
-            Color::Rgb(
-                ((rgb >> 16) & 0xFF) as u8,
-                ((rgb >> 8) & 0xFF) as u8,
-                (rgb & 0xFF) as u8,
-            )
-        } else {
-            Color::White
-        }
+        parse_color(s).unwrap_or(Color::White)
     }
 }
 
-fn resolve_theme(name: &str) -> Theme {
+/// `#rrggbb`, `0xrrggbb` or a terminal color name such as `red` or `reset`.
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    let hex = s.trim_start_matches('#').trim_start_matches("0x");
+    if hex.len() == 6
+        && let Ok(rgb) = u32::from_str_radix(hex, 16)
+    {
+        return Ok(Color::Rgb(
+            ((rgb >> 16) & 0xFF) as u8,
+            ((rgb >> 8) & 0xFF) as u8,
+            (rgb & 0xFF) as u8,
+        ));
+    }
+    if !s.starts_with('#')
+        && let Ok(color) = s.parse::<Color>()
+    {
+        return Ok(color);
+    }
+    Err(format!(
+        "invalid color \"{s}\", expected #rrggbb or a color name like \"red\""
+    ))
+}
+
+/// Bundled themes. User themes are read from `themes/<name>.toml` next to
+/// config.toml and can start from one of these with `inherits = "<name>"`.
+pub const THEMES: [&str; 6] = [
+    "catppuccin_mocha",
+    "dracula",
+    "gruvbox_dark",
+    "nord",
+    "rose_pine",
+    "tokyonight",
+];
+
+/// Used when the configured theme can't be loaded.
+const DEFAULT_THEME: &str = "tokyonight";
+
+fn bundled_theme(name: &str) -> Option<toml::Table> {
     let toml_str = match name {
         "catppuccin_mocha" => include_str!("../themes/catppuccin_mocha.toml"),
         "dracula" => include_str!("../themes/dracula.toml"),
         "gruvbox_dark" => include_str!("../themes/gruvbox_dark.toml"),
         "nord" => include_str!("../themes/nord.toml"),
         "rose_pine" => include_str!("../themes/rose_pine.toml"),
-        _ => include_str!("../themes/tokyonight.toml"),
+        "tokyonight" => include_str!("../themes/tokyonight.toml"),
+        _ => return None,
+    };
+    Some(toml_str.parse().expect("Invalid theme file"))
+}
+
+fn themes_dir() -> PathBuf {
+    get_conf_dir().join("themes")
+}
+
+/// Bundled and user theme names, sorted.
+pub fn theme_names() -> Vec<String> {
+    let mut names: Vec<String> = THEMES.map(str::to_string).to_vec();
+    if let Ok(entries) = fs::read_dir(themes_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "toml")
+                && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Loads a theme by name. A user theme file wins over a bundled theme of the
+/// same name, and is read again on every call so edits show up when the
+/// theme is picked again.
+pub fn resolve_theme(name: &str) -> Result<Theme, String> {
+    let path = themes_dir().join(format!("{name}.toml"));
+    let table = match fs::read_to_string(&path) {
+        Ok(content) => {
+            let mut table: toml::Table = content
+                .parse()
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+            match table.remove("inherits") {
+                Some(toml::Value::String(base)) => {
+                    let mut merged = bundled_theme(&base).ok_or_else(|| {
+                        format!(
+                            "{}: inherits unknown theme \"{base}\", bundled themes are {}",
+                            path.display(),
+                            THEMES.join(", ")
+                        )
+                    })?;
+                    merge(&mut merged, table);
+                    merged
+                }
+                Some(_) => {
+                    return Err(format!(
+                        "{}: inherits has to be the name of a bundled theme",
+                        path.display()
+                    ));
+                }
+                None => table,
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bundled_theme(name).ok_or_else(|| {
+                format!(
+                    "unknown theme \"{name}\", available: {}",
+                    theme_names().join(", ")
+                )
+            })?
+        }
+        Err(e) => return Err(format!("{}: {e}", path.display())),
     };
-    toml::from_str(toml_str).expect("Invalid theme file")
+    check_colors(&table).map_err(|e| format!("theme {name}: {e}"))?;
+    toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| format!("theme {name}: {e}"))
+}
+
+/// Overrides keys of `base` with those of `over`, tables key by key.
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Every value of a theme is a color in a section table.
+fn check_colors(theme: &toml::Table) -> Result<(), String> {
+    for (section, values) in theme {
+        let toml::Value::Table(values) = values else {
+            return Err(format!("{section} has to be a [{section}] table"));
+        };
+        for (key, value) in values {
+            let color = value
+                .as_str()
+                .ok_or_else(|| format!("[{section}] {key}: expected a color string"))?;
+            parse_color(color).map_err(|e| format!("[{section}] {key}: {e}"))?;
+        }
+    }
+    Ok(())
 }
 
 pub fn get_config() -> Config {
     let path = get_conf_dir().join("config.toml");
     let content = fs::read_to_string(&path).expect("config.toml not found");
     let raw: RawConfig = toml::from_str(&content).expect("Invalid config.toml");
+    let local_daemon = is_local_url(&raw.url);
+    let upload_torrent_files = raw.upload_torrent_files.unwrap_or(!local_daemon);
+    let (theme, theme_name) = match resolve_theme(&raw.theme) {
+        Ok(theme) => (theme, raw.theme),
+        Err(e) => {
+            eprintln!("Invalid theme in config.toml: {e}, using {DEFAULT_THEME}");
+            // The bundled one, a broken user file of the same name is skipped.
+            let table = bundled_theme(DEFAULT_THEME).expect("default theme is bundled");
+            let theme = toml::Value::Table(table)
+                .try_into()
+                .expect("Invalid theme file");
+            (theme, DEFAULT_THEME.to_string())
+        }
+    };
+    let keys = Keymap::new(&raw.keys).unwrap_or_else(|errors| {
+        for e in errors {
+            eprintln!("Invalid [keys] in config.toml: {e}");
+        }
+        std::process::exit(1);
+    });
     Config {
         rpc_config: RpcConfig {
             url: raw.url,
             username: raw.username,
             password: raw.password,
         },
-        theme: resolve_theme(&raw.theme),
+        theme,
+        theme_name,
+        geoip_db: raw.geoip_db,
+        upload_torrent_files,
+        local_daemon,
+        watch_dirs: raw
+            .watch_dirs
+            .into_iter()
+            .map(|dir| match dir {
+                RawWatchDir::Path(path) => WatchDir {
+                    path,
+                    download_dir: None,
+                    labels: vec![],
+                    move_to: None,
+                },
+                RawWatchDir::Dir(dir) => dir,
+            })
+            .collect(),
+        bookmarks: raw.bookmarks,
+        mouse: raw.mouse.unwrap_or(true),
+        layout: PaneLayout {
+            split: raw
+                .layout
+                .split
+                .clamp(PaneLayout::MIN_SPLIT, PaneLayout::MAX_SPLIT),
+            ..raw.layout
+        },
+        keys,
+    }
+}
+
+/// Writes the layout back to config.toml. The file is edited as a document,
+/// so comments, formatting and an inline or dotted `layout` stay as they
+/// are, and replaced in one rename.
+pub fn save_layout(layout: &PaneLayout) -> std::io::Result<()> {
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    let path = get_conf_dir().join("config.toml");
+    let mut doc: toml_edit::DocumentMut = fs::read_to_string(&path)?
+        .parse()
+        .map_err(|e: toml_edit::TomlError| invalid(e.to_string()))?;
+    let table = doc
+        .entry("layout")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| invalid("layout is not a table".to_string()))?;
+    set_value(table, "split", i64::from(layout.split).into());
+    set_value(table, "horizontal", layout.horizontal.into());
+    // Inline tables hold no comments, spacing them again is safe.
+    if let Some(inline) = doc["layout"].as_inline_table_mut() {
+        inline.fmt();
+    }
+
+    // Next to config.toml so the rename stays on one filesystem.
+    let tmp = path.with_extension("toml.tmp");
+    fs::write(&tmp, doc.to_string())?;
+    fs::set_permissions(&tmp, fs::metadata(&path)?.permissions())?;
+    fs::rename(&tmp, &path)
+}
+
+/// Replaces a value but keeps the comments around it and its key.
+fn set_value(table: &mut dyn toml_edit::TableLike, key: &str, mut value: toml_edit::Value) {
+    match table.get_mut(key).and_then(toml_edit::Item::as_value_mut) {
+        Some(old) => {
+            *value.decor_mut() = old.decor().clone();
+            *old = value;
+        }
+        None => {
+            table.insert(key, toml_edit::Item::Value(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(parse_color("#ff8000"), Ok(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse_color("0x0A0b0C"), Ok(Color::Rgb(10, 11, 12)));
+        assert!(parse_color("#ff80").is_err());
+        assert!(parse_color("#gggggg").is_err());
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(parse_color("red"), Ok(Color::Red));
+        assert_eq!(parse_color("reset"), Ok(Color::Reset));
+        assert!(parse_color("#red").is_err());
+        assert!(parse_color("reddish").is_err());
+    }
+
+    #[test]
+    fn merge_overrides_key_by_key() {
+        let mut base: toml::Table = toml::from_str(
+            "[general]\nforeground = \"white\"\nbackground = \"black\"\n[table]\nheader = \"blue\"",
+        )
+        .unwrap();
+        let over: toml::Table =
+            toml::from_str("[general]\nforeground = \"red\"\n[details]\naccent_fg = \"green\"")
+                .unwrap();
+        merge(&mut base, over);
+        let expected: toml::Table = toml::from_str(
+            "[general]\nforeground = \"red\"\nbackground = \"black\"\n[table]\nheader = \"blue\"\n[details]\naccent_fg = \"green\"",
+        )
+        .unwrap();
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn bundled_themes_load() {
+        for name in THEMES {
+            let table = bundled_theme(name).unwrap();
+            assert_eq!(check_colors(&table), Ok(()), "{name}");
+            assert!(
+                toml::Value::Table(table).try_into::<Theme>().is_ok(),
+                "{name}"
+            );
+        }
     }
 }
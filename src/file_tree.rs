@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+/// One row of a [`FileTree`]: either a folder or a file of the torrent.
+pub struct Node {
+    pub name: String,
+    /// Full path inside the torrent, folders end with `/`.
+    pub path: String,
+    pub depth: usize,
+    pub is_dir: bool,
+    /// Indices of every file below this node (just itself for a file).
+    pub files: Vec<usize>,
+}
+
+/// Folder tree built from the flat, `/`-separated file list of a torrent.
+pub struct FileTree {
+    pub nodes: Vec<Node>,
+    collapsed: HashSet<String>,
+}
+
+#[derive(Default)]
+struct Dir {
+    dirs: BTreeMap<String, Dir>,
+    files: Vec<(String, usize)>,
+}
+
+impl FileTree {
+    pub fn new<S: AsRef<str>>(paths: &[S]) -> Self {
+        let mut root = Dir::default();
+        for (index, path) in paths.iter().enumerate() {
+            let mut parts: Vec<&str> = path.as_ref().split_terminator('/').collect();
+            let name = parts.pop().unwrap_or_default().to_string();
+            let mut dir = &mut root;
+            for part in parts {
+                dir = dir.dirs.entry(part.to_string()).or_default();
+            }
+            dir.files.push((name, index));
+        }
+
+        let mut nodes = vec![];
+        walk(&root, "", 0, &mut nodes);
+        Self {
+            nodes,
+            collapsed: HashSet::new(),
+        }
+    }
+
+    /// Node indices that are not hidden inside a collapsed folder.
+    pub fn visible(&self) -> Vec<usize> {
+        let mut visible = vec![];
+        let mut skip_below: Option<usize> = None;
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(depth) = skip_below {
+                if node.depth > depth {
+                    continue;
+                }
+                skip_below = None;
+            }
+            if node.is_dir && self.collapsed.contains(&node.path) {
+                skip_below = Some(node.depth);
+            }
+            visible.push(i);
+        }
+        visible
+    }
+
+    pub fn is_collapsed(&self, node: usize) -> bool {
+        self.collapsed.contains(&self.nodes[node].path)
+    }
+
+    pub fn toggle_collapsed(&mut self, node: usize) {
+        let Some(node) = self.nodes.get(node) else {
+            return;
+        };
+        if !node.is_dir {
+            return;
+        }
+        if !self.collapsed.remove(&node.path) {
+            self.collapsed.insert(node.path.clone());
+        }
+    }
+}
+
+fn walk(dir: &Dir, prefix: &str, depth: usize, nodes: &mut Vec<Node>) -> Vec<usize> {
+    let mut all = vec![];
+
+    for (name, sub) in &dir.dirs {
+        let path = format!("{prefix}{name}/");
+        let pos = nodes.len();
+        nodes.push(Node {
+            name: name.clone(),
+            path: path.clone(),
+            depth,
+            is_dir: true,
+            files: vec![],
+        });
+        let files = walk(sub, &path, depth + 1, nodes);
+        all.extend(&files);
+        nodes[pos].files = files;
+    }
+
+    for (name, index) in &dir.files {
+        nodes.push(Node {
+            name: name.clone(),
+            path: format!("{prefix}{name}"),
+            depth,
+            is_dir: false,
+            files: vec![*index],
+        });
+        all.push(*index);
+    }
+
+    all
+}
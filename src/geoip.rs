@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use maxminddb::Reader;
+use maxminddb::geoip2;
+
+use crate::util::expand_path;
+
+/// Offline country lookup backed by a local MaxMind / DB-IP `.mmdb` file.
+pub struct GeoIp {
+    reader: Reader<Vec<u8>>,
+    /// Results survive between poll cycles so every peer is resolved only once.
+    cache: HashMap<IpAddr, Option<String>>,
+}
+
+impl GeoIp {
+    pub fn open(path: &str) -> Option<Self> {
+        match Reader::open_readfile(expand_path(path)) {
+            Ok(reader) => Some(Self {
+                reader,
+                cache: HashMap::new(),
+            }),
+            Err(e) => {
+                eprintln!("Failed to open GeoIP database {path}: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn country(&mut self, ip: IpAddr) -> Option<String> {
+        if let Some(cached) = self.cache.get(&ip) {
+            return cached.clone();
+        }
+
+        let country = self.lookup(ip);
+        self.cache.insert(ip, country.clone());
+        country
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Option<String> {
+        let record = self
+            .reader
+            .lookup(ip)
+            .ok()?
+            .decode::<geoip2::Country>()
+            .ok()??;
+
+        record
+            .country
+            .iso_code
+            .or(record.registered_country.iso_code)
+            .map(|code| code.to_string())
+    }
+}
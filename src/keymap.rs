@@ -0,0 +1,648 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Where a binding is active. Global keys are checked before the pane keys,
+/// the files keys are active together with the details keys. Lists opened
+/// inside a popup, like bookmarks, use the menu keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Context {
+    Global,
+    Torrents,
+    Details,
+    Files,
+    Adder,
+    Picker,
+    Magnet,
+    Batch,
+    Options,
+    Creator,
+    Settings,
+    Stats,
+    Menu,
+    Confirm,
+    Help,
+}
+
+impl Context {
+    pub const ALL: [Context; 15] = [
+        Context::Global,
+        Context::Torrents,
+        Context::Details,
+        Context::Files,
+        Context::Adder,
+        Context::Picker,
+        Context::Magnet,
+        Context::Batch,
+        Context::Options,
+        Context::Creator,
+        Context::Settings,
+        Context::Stats,
+        Context::Menu,
+        Context::Confirm,
+        Context::Help,
+    ];
+
+    /// Name of the `[keys.<name>]` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Torrents => "torrents",
+            Context::Details => "details",
+            Context::Files => "files",
+            Context::Adder => "adder",
+            Context::Picker => "picker",
+            Context::Magnet => "magnet",
+            Context::Batch => "batch",
+            Context::Options => "options",
+            Context::Creator => "creator",
+            Context::Settings => "settings",
+            Context::Stats => "stats",
+            Context::Menu => "menu",
+            Context::Confirm => "confirm",
+            Context::Help => "help",
+        }
+    }
+
+    /// Contexts whose keys are handled at the same time, so they can't share a chord.
+    fn overlaps(self, other: Context) -> bool {
+        use Context::*;
+        self == other
+            || matches!(
+                (self, other),
+                (Global, Torrents | Details | Files)
+                    | (Torrents | Details | Files, Global)
+                    | (Details, Files)
+                    | (Files, Details)
+            )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    AddTorrent,
+    AddMagnet,
+    CreateTorrent,
+    ToggleTurtle,
+    Statistics,
+    Settings,
+    Down,
+    Up,
+    First,
+    Last,
+    NextTab,
+    PrevTab,
+    FocusTop,
+    FocusBottom,
+    OpenMenu,
+    Delete,
+    DeleteWithData,
+    Pause,
+    Resume,
+    Fold,
+    ToggleWanted,
+    CyclePriority,
+    ToggleAll,
+    SelectPattern,
+    DeselectPattern,
+    Options,
+    Back,
+    Open,
+    Mark,
+    PickCurrent,
+    AddFolder,
+    Preview,
+    ToggleHidden,
+    Bookmarks,
+    GoTo,
+    Filter,
+    EditLink,
+    Remove,
+    Edit,
+    Browse,
+    Recent,
+    Create,
+    Undo,
+    Save,
+    SwitchTable,
+    Refresh,
+    Confirm,
+    Close,
+    Help,
+    CommandLine,
+    GrowPane,
+    ShrinkPane,
+    ZoomPane,
+    ToggleLayout,
+    ThemePicker,
+}
+
+/// Every bindable action with its config name, help text and default keys.
+#[rustfmt::skip]
+const BINDINGS: &[(Context, Action, &str, &str, &[&str])] = &[
+    (Context::Global, Action::Quit, "quit", "Quit", &["q"]),
+    (Context::Global, Action::AddTorrent, "add_torrent", "Add .torrent files", &["a"]),
+    (Context::Global, Action::AddMagnet, "add_magnet", "Add a magnet link", &["m"]),
+    (Context::Global, Action::CreateTorrent, "create_torrent", "Create a torrent", &["c"]),
+    (Context::Global, Action::ToggleTurtle, "toggle_turtle", "Toggle turtle mode", &["t"]),
+    (Context::Global, Action::Statistics, "statistics", "Statistics", &["i"]),
+    (Context::Global, Action::Settings, "settings", "Session settings", &["S"]),
+    (Context::Global, Action::Help, "help", "Show keys", &["?"]),
+    (Context::Global, Action::CommandLine, "command_line", "Command line", &[":"]),
+    (Context::Global, Action::GrowPane, "grow_pane", "Grow the focused pane", &["+", "="]),
+    (Context::Global, Action::ShrinkPane, "shrink_pane", "Shrink the focused pane", &["-"]),
+    (Context::Global, Action::ZoomPane, "zoom_pane", "Maximize the focused pane", &["z"]),
+    (Context::Global, Action::ToggleLayout, "toggle_layout", "Stack or place panes side by side", &["L"]),
+    (Context::Global, Action::ThemePicker, "theme_picker", "Pick a theme", &["T"]),
+
+    (Context::Torrents, Action::Down, "down", "Next torrent", &["j", "down"]),
+    (Context::Torrents, Action::Up, "up", "Previous torrent", &["k", "up"]),
+    (Context::Torrents, Action::NextTab, "next_tab", "Next tab", &["l", "right"]),
+    (Context::Torrents, Action::PrevTab, "prev_tab", "Previous tab", &["h", "left"]),
+    (Context::Torrents, Action::FocusBottom, "focus_bottom", "Focus the bottom pane", &["ctrl-j"]),
+    (Context::Torrents, Action::OpenMenu, "action_menu", "Torrent actions", &["K"]),
+    (Context::Torrents, Action::Pause, "pause", "Pause", &["p"]),
+    (Context::Torrents, Action::Resume, "resume", "Resume", &["r"]),
+    (Context::Torrents, Action::Delete, "delete", "Remove torrent", &["d"]),
+    (Context::Torrents, Action::DeleteWithData, "delete_with_data", "Remove torrent and data", &["D"]),
+
+    (Context::Details, Action::NextTab, "next_tab", "Next tab", &["l", "right"]),
+    (Context::Details, Action::PrevTab, "prev_tab", "Previous tab", &["h", "left"]),
+    (Context::Details, Action::FocusTop, "focus_top", "Focus the torrent list", &["ctrl-k"]),
+
+    (Context::Files, Action::Down, "down", "Next file", &["j", "down"]),
+    (Context::Files, Action::Up, "up", "Previous file", &["k", "up"]),
+    (Context::Files, Action::First, "first", "First file", &["g", "home"]),
+    (Context::Files, Action::Last, "last", "Last file", &["G", "end"]),
+    (Context::Files, Action::Fold, "fold", "Fold or unfold folder", &["enter", "tab"]),
+    (Context::Files, Action::ToggleWanted, "toggle_wanted", "Download or skip", &["space"]),
+    (Context::Files, Action::CyclePriority, "cycle_priority", "Cycle priority", &["p"]),
+
+    (Context::Adder, Action::Down, "down", "Next file", &["j", "down"]),
+    (Context::Adder, Action::Up, "up", "Previous file", &["k", "up"]),
+    (Context::Adder, Action::Fold, "fold", "Fold or unfold folder", &["tab"]),
+    (Context::Adder, Action::ToggleWanted, "toggle_wanted", "Select or deselect", &["space"]),
+    (Context::Adder, Action::CyclePriority, "cycle_priority", "Cycle priority", &["p"]),
+    (Context::Adder, Action::ToggleAll, "toggle_all", "Select all or none", &["a"]),
+    (Context::Adder, Action::SelectPattern, "select_pattern", "Select files matching a pattern", &["+"]),
+    (Context::Adder, Action::DeselectPattern, "deselect_pattern", "Deselect files matching a pattern", &["-"]),
+    (Context::Adder, Action::Options, "options", "Add options", &["o"]),
+    (Context::Adder, Action::Confirm, "confirm", "Add the torrent", &["enter"]),
+    (Context::Adder, Action::Close, "close", "Cancel", &["q", "esc", "ctrl-["]),
+    (Context::Adder, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Picker, Action::Down, "down", "Next entry", &["j", "down"]),
+    (Context::Picker, Action::Up, "up", "Previous entry", &["k", "up"]),
+    (Context::Picker, Action::Back, "back", "Parent folder", &["h", "left"]),
+    (Context::Picker, Action::Open, "open", "Open folder or add torrent", &["l", "enter", "right"]),
+    (Context::Picker, Action::Mark, "mark", "Mark a torrent, or pick the entry", &["space"]),
+    (Context::Picker, Action::PickCurrent, "pick_current", "Pick the current folder", &["."]),
+    (Context::Picker, Action::AddFolder, "add_folder", "Add every torrent listed", &["A"]),
+    (Context::Picker, Action::Preview, "preview", "Show torrent info", &["i"]),
+    (Context::Picker, Action::ToggleHidden, "toggle_hidden", "Show hidden files", &["H"]),
+    (Context::Picker, Action::Bookmarks, "bookmarks", "Bookmarks", &["b"]),
+    (Context::Picker, Action::GoTo, "go_to", "Go to a path", &["g"]),
+    (Context::Picker, Action::Filter, "filter", "Search", &["/"]),
+    (Context::Picker, Action::Close, "close", "Clear the search or close", &["q", "esc", "ctrl-["]),
+    (Context::Picker, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Magnet, Action::Confirm, "confirm", "Add the magnet link", &["enter"]),
+    (Context::Magnet, Action::Options, "options", "Add options", &["o"]),
+    (Context::Magnet, Action::EditLink, "edit_link", "Edit the link", &["e"]),
+    (Context::Magnet, Action::Close, "close", "Cancel", &["q", "esc", "ctrl-["]),
+    (Context::Magnet, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Batch, Action::Down, "down", "Next torrent", &["j", "down"]),
+    (Context::Batch, Action::Up, "up", "Previous torrent", &["k", "up"]),
+    (Context::Batch, Action::Remove, "remove", "Leave the torrent out", &["x", "delete"]),
+    (Context::Batch, Action::Options, "options", "Add options", &["o"]),
+    (Context::Batch, Action::Confirm, "confirm", "Add the torrents", &["enter"]),
+    (Context::Batch, Action::Close, "close", "Cancel", &["q", "esc", "ctrl-["]),
+    (Context::Batch, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Options, Action::Down, "down", "Next option", &["j", "down"]),
+    (Context::Options, Action::Up, "up", "Previous option", &["k", "up"]),
+    (Context::Options, Action::Edit, "edit", "Edit or toggle the option", &["enter", "space"]),
+    (Context::Options, Action::Browse, "browse", "Browse for the download dir", &["d"]),
+    (Context::Options, Action::Recent, "recent", "Recent download dirs", &["r"]),
+    (Context::Options, Action::Close, "close", "Back to the list", &["o", "q", "esc", "ctrl-["]),
+    (Context::Options, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Creator, Action::Down, "down", "Next field", &["j", "down"]),
+    (Context::Creator, Action::Up, "up", "Previous field", &["k", "up"]),
+    (Context::Creator, Action::Edit, "edit", "Edit or toggle the field", &["enter", "space"]),
+    (Context::Creator, Action::Browse, "browse", "Browse for the data", &["d"]),
+    (Context::Creator, Action::Create, "create", "Create the torrent", &["c"]),
+    (Context::Creator, Action::Close, "close", "Cancel", &["q", "esc", "ctrl-["]),
+    (Context::Creator, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Settings, Action::Down, "down", "Next setting", &["j", "down"]),
+    (Context::Settings, Action::Up, "up", "Previous setting", &["k", "up"]),
+    (Context::Settings, Action::Edit, "edit", "Edit or toggle the setting", &["enter", "space"]),
+    (Context::Settings, Action::Undo, "undo", "Undo the change", &["u"]),
+    (Context::Settings, Action::Save, "save", "Save the changes", &["s"]),
+    (Context::Settings, Action::Close, "close", "Close", &["q", "esc", "ctrl-["]),
+    (Context::Settings, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Stats, Action::Down, "down", "Next row", &["j", "down"]),
+    (Context::Stats, Action::Up, "up", "Previous row", &["k", "up"]),
+    (Context::Stats, Action::SwitchTable, "switch_table", "Switch table", &["tab", "h", "l"]),
+    (Context::Stats, Action::Refresh, "refresh", "Refresh", &["r"]),
+    (Context::Stats, Action::Close, "close", "Close", &["q", "esc", "ctrl-["]),
+    (Context::Stats, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Menu, Action::Down, "down", "Next entry", &["j", "down"]),
+    (Context::Menu, Action::Up, "up", "Previous entry", &["k", "up"]),
+    (Context::Menu, Action::Confirm, "confirm", "Run the entry", &["enter"]),
+    (Context::Menu, Action::Close, "close", "Close", &["q", "esc", "ctrl-["]),
+    (Context::Menu, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Confirm, Action::Confirm, "confirm", "Yes", &["y", "Y"]),
+    (Context::Confirm, Action::Close, "close", "No", &["n", "N", "q", "ctrl-["]),
+    (Context::Confirm, Action::Help, "help", "Show keys", &["?"]),
+
+    (Context::Help, Action::Down, "down", "Scroll down", &["j", "down"]),
+    (Context::Help, Action::Up, "up", "Scroll up", &["k", "up"]),
+    (Context::Help, Action::Close, "close", "Close", &["q", "esc", "ctrl-["]),
+];
+
+/// A key with the modifiers that matter for matching. Shift is part of the
+/// character itself, `K` rather than `shift-k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+}
+
+impl From<&KeyEvent> for KeyChord {
+    fn from(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // `-` alone is a key, so only split while a modifier prefix is left.
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if let Some(r) = lower.strip_prefix("ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[rest.len() - r.len()..];
+            } else if let Some(r) = lower.strip_prefix("alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[rest.len() - r.len()..];
+            } else if let Some(r) = lower.strip_prefix("shift-") {
+                modifiers |= KeyModifiers::SHIFT;
+                rest = &rest[rest.len() - r.len()..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{s}\"")),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            code => write!(f, "{}", format!("{code:?}").to_lowercase()),
+        }
+    }
+}
+
+/// One key or a list of keys for an action in the `[keys]` table.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum RawKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// `[keys.<context>]` tables, action name to keys.
+pub type RawKeymap = HashMap<String, HashMap<String, RawKeys>>;
+
+#[derive(Debug, Clone)]
+struct Binding {
+    context: Context,
+    action: Action,
+    description: &'static str,
+    keys: Vec<KeyChord>,
+}
+
+/// Key chords resolved to actions per context.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&RawKeymap::new()).expect("default keys are valid")
+    }
+}
+
+impl Keymap {
+    /// Defaults with the keys from config applied. Keys set for an action
+    /// replace its defaults, an empty list unbinds it.
+    pub fn new(raw: &RawKeymap) -> Result<Self, Vec<String>> {
+        let mut errors = vec![];
+        let mut bindings: Vec<Binding> = BINDINGS
+            .iter()
+            .map(|(context, action, _, description, keys)| Binding {
+                context: *context,
+                action: *action,
+                description,
+                keys: keys.iter().map(|k| k.parse().unwrap()).collect(),
+            })
+            .collect();
+
+        for (context_name, actions) in raw {
+            let Some(context) = Context::ALL.into_iter().find(|c| c.name() == context_name) else {
+                errors.push(format!("[keys.{context_name}]: unknown context"));
+                continue;
+            };
+            for (action_name, keys) in actions {
+                let Some(index) = BINDINGS
+                    .iter()
+                    .position(|(c, _, name, _, _)| *c == context && name == action_name)
+                else {
+                    errors.push(format!(
+                        "[keys.{context_name}] {action_name}: unknown action"
+                    ));
+                    continue;
+                };
+                let keys = match keys {
+                    RawKeys::One(key) => std::slice::from_ref(key),
+                    RawKeys::Many(keys) => keys.as_slice(),
+                };
+                let mut chords = vec![];
+                for key in keys {
+                    match key.parse() {
+                        Ok(chord) => chords.push(chord),
+                        Err(e) => errors.push(format!("[keys.{context_name}] {action_name}: {e}")),
+                    }
+                }
+                bindings[index].keys = chords;
+            }
+        }
+
+        for (i, a) in bindings.iter().enumerate() {
+            for b in &bindings[i + 1..] {
+                if !a.context.overlaps(b.context) {
+                    continue;
+                }
+                for key in a.keys.iter().filter(|k| b.keys.contains(k)) {
+                    errors.push(format!(
+                        "key \"{key}\" is bound to both {}.{} and {}.{}",
+                        a.context.name(),
+                        action_name(a.context, a.action),
+                        b.context.name(),
+                        action_name(b.context, b.action),
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self { bindings })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Help text and keys of the bound actions in a context, in table order.
+    pub fn bindings(&self, context: Context) -> impl Iterator<Item = (&'static str, &[KeyChord])> {
+        self.bindings
+            .iter()
+            .filter(move |b| b.context == context && !b.keys.is_empty())
+            .map(|b| (b.description, b.keys.as_slice()))
+    }
+
+    /// The first key bound to the action as shown in hints, `None` when the
+    /// action is unbound.
+    pub fn key(&self, context: Context, action: Action) -> Option<String> {
+        let key = self
+            .bindings
+            .iter()
+            .find(|b| b.context == context && b.action == action)?
+            .keys
+            .first()?
+            .to_string();
+        // Named keys read as `Enter` or `Esc`, like the rest of the text.
+        Some(match key.chars().next() {
+            Some(first) if key.len() > 1 && key.chars().all(|c| c.is_ascii_alphabetic()) => {
+                first.to_ascii_uppercase().to_string() + &key[1..]
+            }
+            _ => key,
+        })
+    }
+
+    /// `[key label]` for footers and titles, `None` when the action is unbound.
+    pub fn hint(&self, context: Context, action: Action, label: &str) -> Option<String> {
+        self.key(context, action)
+            .map(|key| format!("[{key} {label}]"))
+    }
+
+    /// The hints of the bound actions joined with `sep`.
+    pub fn hints(&self, context: Context, hints: &[(Action, &str)], sep: &str) -> String {
+        hints
+            .iter()
+            .filter_map(|(action, label)| self.hint(context, *action, label))
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from(key);
+        self.bindings
+            .iter()
+            .find(|b| b.context == context && b.keys.contains(&chord))
+            .map(|b| b.action)
+    }
+}
+
+fn action_name(context: Context, action: Action) -> &'static str {
+    BINDINGS
+        .iter()
+        .find(|(c, a, _, _, _)| *c == context && *a == action)
+        .map_or("?", |(_, _, name, _, _)| name)
+}
+
+/// The first action called `name` in the given contexts, for running actions
+/// from the command line.
+pub fn find_action(contexts: &[Context], name: &str) -> Option<(Context, Action)> {
+    contexts.iter().find_map(|context| {
+        BINDINGS
+            .iter()
+            .find(|(c, _, n, _, _)| c == context && *n == name)
+            .map(|(c, a, _, _, _)| (*c, *a))
+    })
+}
+
+/// Config names of the actions in the given contexts.
+pub fn action_names(contexts: &[Context]) -> impl Iterator<Item = &'static str> {
+    BINDINGS
+        .iter()
+        .filter(|(c, _, _, _, _)| contexts.contains(c))
+        .map(|(_, _, name, _, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(toml: &str) -> Result<Keymap, Vec<String>> {
+        Keymap::new(&toml::from_str(toml).unwrap())
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert!(keymap("").is_ok());
+    }
+
+    #[test]
+    fn config_keys_replace_defaults() {
+        let keys = keymap("[torrents]\npause = [\"space\", \"ctrl-p\"]").unwrap();
+        let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert_eq!(keys.action(Context::Torrents, &ctrl_p), Some(Action::Pause));
+        assert_eq!(
+            keys.action(Context::Torrents, &press(KeyCode::Char(' '))),
+            Some(Action::Pause)
+        );
+        assert_eq!(
+            keys.action(Context::Torrents, &press(KeyCode::Char('p'))),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_list_unbinds() {
+        let keys = keymap("[global]\nquit = []").unwrap();
+        assert_eq!(
+            keys.action(Context::Global, &press(KeyCode::Char('q'))),
+            None
+        );
+        assert_eq!(keys.hint(Context::Global, Action::Quit, "quit"), None);
+    }
+
+    #[test]
+    fn conflict_in_one_context() {
+        let errors = keymap("[torrents]\npause = \"r\"").unwrap_err();
+        assert_eq!(
+            errors,
+            ["key \"r\" is bound to both torrents.pause and torrents.resume"]
+        );
+    }
+
+    #[test]
+    fn conflict_with_global_keys() {
+        let errors = keymap("[torrents]\npause = \"a\"").unwrap_err();
+        assert_eq!(
+            errors,
+            ["key \"a\" is bound to both global.add_torrent and torrents.pause"]
+        );
+    }
+
+    #[test]
+    fn separate_popups_share_keys() {
+        assert!(keymap("[adder]\noptions = \"x\"\n[picker]\nmark = \"x\"").is_ok());
+    }
+
+    #[test]
+    fn unknown_names_and_keys() {
+        let errors = keymap("[nowhere]\nquit = \"q\"").unwrap_err();
+        assert_eq!(errors, ["[keys.nowhere]: unknown context"]);
+        let errors = keymap("[global]\nfly = \"f\"").unwrap_err();
+        assert_eq!(errors, ["[keys.global] fly: unknown action"]);
+        let errors = keymap("[global]\nquit = \"hyper-q\"").unwrap_err();
+        assert_eq!(errors, ["[keys.global] quit: unknown key \"hyper-q\""]);
+    }
+
+    #[test]
+    fn hints_use_the_first_key() {
+        let keys = Keymap::default();
+        assert_eq!(
+            keys.hint(Context::Adder, Action::Confirm, "add").as_deref(),
+            Some("[Enter add]")
+        );
+        let keys = keymap("[adder]\nclose = [\"ctrl-g\", \"esc\"]").unwrap();
+        assert_eq!(
+            keys.hint(Context::Adder, Action::Close, "cancel")
+                .as_deref(),
+            Some("[ctrl-g cancel]")
+        );
+    }
+}
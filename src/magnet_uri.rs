@@ -0,0 +1,148 @@
+use url::Url;
+
+/// The parts of a `magnet:` link that can be shown before adding it.
+pub struct MagnetUri {
+    pub name: Option<String>,
+    /// BitTorrent v1 info-hash as lowercase hex.
+    pub info_hash_v1: Option<String>,
+    /// BitTorrent v2 (SHA-256) info-hash as lowercase hex.
+    pub info_hash_v2: Option<String>,
+    pub trackers: Vec<String>,
+    pub length: Option<u64>,
+}
+
+impl MagnetUri {
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let url = Url::parse(link.trim()).map_err(|e| format!("Not a valid URI: {e}"))?;
+        if url.scheme() != "magnet" {
+            return Err("Link must start with magnet:?".to_string());
+        }
+
+        let mut magnet = Self {
+            name: None,
+            info_hash_v1: None,
+            info_hash_v2: None,
+            trackers: vec![],
+            length: None,
+        };
+
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "dn" => magnet.name = Some(value.to_string()),
+                "tr" => magnet.trackers.push(value.to_string()),
+                "xl" => {
+                    let length = value
+                        .parse()
+                        .map_err(|_| format!("Invalid exact length: {value}"))?;
+                    magnet.length = Some(length);
+                }
+                "xt" => {
+                    if let Some(hash) = value.strip_prefix("urn:btih:") {
+                        magnet.info_hash_v1 = Some(parse_btih(hash)?);
+                    } else if let Some(hash) = value.strip_prefix("urn:btmh:") {
+                        magnet.info_hash_v2 = Some(parse_btmh(hash)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if magnet.info_hash_v1.is_none() && magnet.info_hash_v2.is_none() {
+            return Err("Missing info-hash (xt=urn:btih: or urn:btmh:)".to_string());
+        }
+        Ok(magnet)
+    }
+}
+
+/// A v1 hash is either 40 hex characters or 32 base32 characters.
+fn parse_btih(hash: &str) -> Result<String, String> {
+    if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(hash.to_lowercase());
+    }
+    if hash.len() == 32
+        && let Some(bytes) = base32_decode(hash)
+    {
+        return Ok(bytes.iter().map(|b| format!("{b:02x}")).collect());
+    }
+    Err(format!("Invalid btih info-hash: {hash}"))
+}
+
+/// A v2 hash is a SHA-256 multihash: the `1220` prefix followed by 64 hex characters.
+fn parse_btmh(hash: &str) -> Result<String, String> {
+    match hash.strip_prefix("1220") {
+        Some(digest) if digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok(digest.to_lowercase())
+        }
+        _ => Err(format!("Invalid btmh info-hash: {hash}")),
+    }
+}
+
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in s.chars() {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+
+    #[test]
+    fn parses_v1_link() {
+        let magnet = MagnetUri::parse(&format!(
+            "magnet:?xt=urn:btih:{}&dn=Some+Name&tr=udp%3A%2F%2Ftracker.example%3A80&tr=http%3A%2F%2Fb.example%2Fannounce&xl=1024",
+            HEX.to_uppercase()
+        ))
+        .unwrap();
+        assert_eq!(magnet.info_hash_v1.as_deref(), Some(HEX));
+        assert_eq!(magnet.info_hash_v2, None);
+        assert_eq!(magnet.name.as_deref(), Some("Some Name"));
+        assert_eq!(
+            magnet.trackers,
+            ["udp://tracker.example:80", "http://b.example/announce"]
+        );
+        assert_eq!(magnet.length, Some(1024));
+    }
+
+    #[test]
+    fn decodes_base32_hash() {
+        let magnet =
+            MagnetUri::parse("magnet:?xt=urn:btih:YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK").unwrap();
+        assert_eq!(magnet.info_hash_v1.as_deref(), Some(HEX));
+    }
+
+    #[test]
+    fn parses_v2_hash() {
+        let digest = "a".repeat(64);
+        let magnet = MagnetUri::parse(&format!("magnet:?xt=urn:btmh:1220{digest}")).unwrap();
+        assert_eq!(magnet.info_hash_v1, None);
+        assert_eq!(magnet.info_hash_v2, Some(digest));
+    }
+
+    #[test]
+    fn rejects_bad_links() {
+        assert!(MagnetUri::parse("not a link").is_err());
+        assert!(MagnetUri::parse(&format!("http://example.com/?xt=urn:btih:{HEX}")).is_err());
+        assert!(MagnetUri::parse("magnet:?dn=name").is_err());
+        assert!(MagnetUri::parse("magnet:?xt=urn:btih:abc").is_err());
+        assert!(MagnetUri::parse("magnet:?xt=urn:btmh:1220abc").is_err());
+        assert!(MagnetUri::parse(&format!("magnet:?xt=urn:btih:{HEX}&xl=big")).is_err());
+    }
+}
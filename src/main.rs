@@ -1,14 +1,26 @@
 mod app;
+mod command;
 mod config;
+mod file_tree;
+mod geoip;
+mod keymap;
+mod magnet_uri;
+mod session;
+mod ui_state;
 mod util;
+mod watch;
 mod widgets;
 use crate::app::App;
 use crate::config::get_config;
+use crate::session::SessionClient;
+use crate::session::SessionStatus;
 use crate::util::get_client;
 
 use crossterm::ExecutableCommand;
 use crossterm::event::DisableBracketedPaste;
+use crossterm::event::DisableMouseCapture;
 use crossterm::event::EnableBracketedPaste;
+use crossterm::event::EnableMouseCapture;
 use crossterm::event::KeyboardEnhancementFlags;
 use crossterm::event::PushKeyboardEnhancementFlags;
 use transmission_rpc::types::Torrent;
@@ -49,11 +61,39 @@ async fn tokio_main() -> color_eyre::Result<()> {
         }
     });
 
-    let app = App::new(client, torrents_arc, config.theme);
+    // Alt-speed state and free space for the status bar
+    let status_arc = Arc::new(Mutex::new(SessionStatus::default()));
+    let status_bg = status_arc.clone();
+    let client_status = client.clone();
+    let mut session = SessionClient::new(config.rpc_config.clone());
+    tokio::spawn(async move {
+        loop {
+            let status = SessionStatus::fetch(&mut session, &client_status).await;
+            *status_bg.lock().await = status;
+            sleep(Duration::from_secs(5)).await;
+        }
+    });
+
+    // Errors from the watch dirs, shown by the app
+    let watch_errors = Arc::new(Mutex::new(vec![]));
+    if !config.watch_dirs.is_empty() {
+        tokio::spawn(watch::watch(
+            client.clone(),
+            config.watch_dirs.clone(),
+            config.upload_torrent_files,
+            watch_errors.clone(),
+        ));
+    }
+
+    let mouse = config.mouse;
+    let app = App::new(client, torrents_arc, status_arc, watch_errors, config);
     let mut terminal = ratatui::init();
 
     terminal.clear()?;
     std::io::stdout().execute(EnableBracketedPaste)?;
+    if mouse {
+        std::io::stdout().execute(EnableMouseCapture)?;
+    }
     if cfg!(not(windows)) {
         std::io::stdout().execute(PushKeyboardEnhancementFlags(
             KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES,
@@ -63,6 +103,9 @@ async fn tokio_main() -> color_eyre::Result<()> {
     let result = app.run(terminal).await;
 
     std::io::stdout().execute(DisableBracketedPaste)?;
+    if mouse {
+        std::io::stdout().execute(DisableMouseCapture)?;
+    }
     ratatui::restore();
 
     result
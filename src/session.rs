@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use serde_json::{Map, Value, json};
+use tokio::sync::Mutex;
+use transmission_rpc::TransClient;
+
+use crate::config::RpcConfig;
+
+/// Raw `session-get` access. The typed response of transmission-rpc only has a
+/// handful of fields, the settings screen needs all of them.
+pub struct SessionClient {
+    http: reqwest::Client,
+    rpc_config: RpcConfig,
+    session_id: Option<String>,
+}
+
+impl SessionClient {
+    pub fn new(rpc_config: RpcConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            rpc_config,
+            session_id: None,
+        }
+    }
+
+    /// Every session argument the daemon reports, keyed by RPC name.
+    pub async fn session_get(&mut self) -> Result<Map<String, Value>, String> {
+        let body = json!({ "method": "session-get" });
+        // The first request of a session is answered with 409 and the id to use.
+        for _ in 0..2 {
+            let mut request = self
+                .http
+                .post(&self.rpc_config.url)
+                .basic_auth(&self.rpc_config.username, Some(&self.rpc_config.password))
+                .json(&body);
+            if let Some(id) = &self.session_id {
+                request = request.header("X-Transmission-Session-Id", id);
+            }
+            let response = request.send().await.map_err(|e| e.to_string())?;
+
+            if response.status() == StatusCode::CONFLICT {
+                self.session_id = response
+                    .headers()
+                    .get("X-Transmission-Session-Id")
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                continue;
+            }
+
+            let mut reply: Value = response.json().await.map_err(|e| e.to_string())?;
+            if reply["result"] != "success" {
+                return Err(reply["result"]
+                    .as_str()
+                    .unwrap_or("unknown error")
+                    .to_string());
+            }
+            return match reply["arguments"].take() {
+                Value::Object(arguments) => Ok(arguments),
+                _ => Err("session-get returned no arguments".to_string()),
+            };
+        }
+        Err("no session id received".to_string())
+    }
+}
+
+/// Daemon state shown in the status bar, refreshed in the background.
+#[derive(Clone, Default)]
+pub struct SessionStatus {
+    pub alt_speed_enabled: Option<bool>,
+    /// Free space in the default download dir.
+    pub free_space: Option<i64>,
+}
+
+impl SessionStatus {
+    pub async fn fetch(session: &mut SessionClient, client: &Arc<Mutex<TransClient>>) -> Self {
+        let Ok(values) = session.session_get().await else {
+            return Self::default();
+        };
+        let download_dir = values
+            .get("download-dir")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let free_space = match download_dir {
+            Some(dir) => match client.lock().await.free_space(dir).await {
+                Ok(resp) if resp.is_ok() => Some(resp.arguments.size_bytes),
+                _ => None,
+            },
+            None => None,
+        };
+        Self {
+            alt_speed_enabled: values.get("alt-speed-enabled").and_then(Value::as_bool),
+            free_space,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::get_state_dir;
+
+/// Tabs, selection, sort, filter and picker directory as they were when
+/// transent last quit. Unknown or stale values are ignored on restore.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub top_tab: usize,
+    pub bottom_tab: usize,
+    /// Hash of the selected torrent, row indexes change between sessions.
+    pub selected: Option<String>,
+    /// As given to `:sort`, e.g. `ratio desc`.
+    pub sort: Option<String>,
+    /// As given to `:filter`.
+    pub filter: String,
+    pub picker_dir: Option<String>,
+}
+
+impl UiState {
+    pub fn load() -> Self {
+        fs::read_to_string(path())
+            .ok()
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Ok(content) = toml::to_string(self) else {
+            return;
+        };
+        let _ = fs::create_dir_all(get_state_dir());
+        let _ = fs::write(path(), content);
+    }
+}
+
+fn path() -> PathBuf {
+    get_state_dir().join("ui_state.toml")
+}
@@ -1,8 +1,8 @@
 use home::home_dir;
-use ratatui::layout::Constraint;
-use ratatui::layout::Direction;
-use ratatui::layout::Layout;
+use ratatui::layout::Position;
 use ratatui::layout::Rect;
+use ratatui::layout::Size;
+use regex::Regex;
 use std::cmp::Ordering;
 use std::fs::DirEntry;
 use std::fs::read_dir;
@@ -30,6 +30,17 @@ pub fn get_client(rpc_config: &RpcConfig) -> color_eyre::Result<Arc<Mutex<TransC
     Ok(client)
 }
 
+/// Whether the RPC endpoint lives on this machine and can read our files.
+pub fn is_local_url(url: &str) -> bool {
+    match Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+    {
+        Some(host) => matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]" | "::1"),
+        None => true,
+    }
+}
+
 pub fn expand_path<P: AsRef<Path>>(path: P) -> PathBuf {
     let p = path.as_ref();
     if let Some(str_path) = p.to_str()
@@ -41,7 +52,48 @@ pub fn expand_path<P: AsRef<Path>>(path: P) -> PathBuf {
     p.to_path_buf()
 }
 
-pub fn get_entries(path: String, show_hidden: bool) -> Vec<DirEntry> {
+/// Completes the last segment of a typed path, keeping a leading `~/` as typed.
+///
+/// A unique match is completed fully, with a trailing `/` for directories.
+/// Several matches are completed up to their longest common prefix.
+pub fn complete_path(partial: &str) -> String {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(i) => partial.split_at(i + 1),
+        None => ("", partial),
+    };
+    let search_dir = if dir.is_empty() { "." } else { dir };
+    let Ok(entries) = read_dir(expand_path(search_dir)) else {
+        return partial.to_string();
+    };
+
+    let matches: Vec<(String, bool)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let hidden = name.starts_with('.') && !prefix.starts_with('.');
+            (name.starts_with(prefix) && !hidden).then(|| (name, e.path().is_dir()))
+        })
+        .collect();
+
+    match matches.as_slice() {
+        [] => partial.to_string(),
+        [(name, is_dir)] => format!("{dir}{name}{}", if *is_dir { "/" } else { "" }),
+        [(first, _), rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, (name, _)| {
+                let len = common
+                    .char_indices()
+                    .zip(name.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map_or(0, |((i, c), _)| i + c.len_utf8());
+                &common[..len]
+            });
+            format!("{dir}{common}")
+        }
+    }
+}
+
+pub fn get_entries(path: String, show_hidden: bool, torrents_only: bool) -> Vec<DirEntry> {
     let real_path = expand_path(path);
 
     let mut entries: Vec<DirEntry> = match read_dir(&real_path) {
@@ -62,11 +114,12 @@ pub fn get_entries(path: String, show_hidden: bool) -> Vec<DirEntry> {
 
                     if path.is_dir()
                         || (path.is_file()
-                            && path
-                                .extension()
-                                .and_then(|ext| ext.to_str())
-                                .map(|ext| ext.eq_ignore_ascii_case("torrent"))
-                                .unwrap_or(false))
+                            && (!torrents_only
+                                || path
+                                    .extension()
+                                    .and_then(|ext| ext.to_str())
+                                    .map(|ext| ext.eq_ignore_ascii_case("torrent"))
+                                    .unwrap_or(false)))
                     {
                         Some(e)
                     } else {
@@ -117,6 +170,34 @@ pub fn fuzzy_match(text: &str, query: &str) -> bool {
     false
 }
 
+/// Compile a file selection pattern into a regex matched against torrent paths.
+///
+/// Patterns prefixed with `re:` are used as regular expressions. Anything else is a
+/// glob where `*` and `?` stay inside one path segment and `**` crosses folders. Globs
+/// are matched against the end of the path, so `*.nfo` hits every folder and
+/// `Season 2/*` matches that season wherever it sits.
+pub fn path_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    if let Some(re) = pattern.strip_prefix("re:") {
+        return Regex::new(re);
+    }
+
+    let mut re = String::from("(^|/)");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                re.push_str(".*");
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re)
+}
+
 /// Calculate a match score for ranking fuzzy matches.
 /// Higher scores indicate better matches.
 pub fn calculate_match_score(text: &str, query: &str) -> usize {
@@ -187,8 +268,33 @@ pub fn readable_size(size: u64) -> String {
         return round_to_2_decimals(size / gb).to_string() + " GB";
     } else if size >= mb {
         return round_to_2_decimals(size / mb).to_string() + " MB";
+    } else if size >= kb {
+        return round_to_2_decimals(size / kb).to_string() + " KB";
     }
-    round_to_2_decimals(size).to_string() + " KB"
+    round_to_2_decimals(size).to_string() + " B"
+}
+
+/// Formats a unix timestamp as a UTC date, e.g. `2024-05-01 13:37 UTC`.
+pub fn readable_date(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86400);
+    let secs = timestamp.rem_euclid(86400);
+
+    // Civil date from days since the epoch, Howard Hinnant's algorithm.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        secs / 3600,
+        (secs % 3600) / 60
+    )
 }
 
 pub fn readable_time(sec: i64) -> String {
@@ -224,6 +330,39 @@ pub fn status_to_string(status: TorrentStatus) -> String {
     s.to_string()
 }
 
+/// Short status for narrow columns, at most seven characters.
+pub fn status_to_short(status: TorrentStatus) -> &'static str {
+    match status {
+        TorrentStatus::Stopped => "Stopped",
+        TorrentStatus::QueuedToVerify
+        | TorrentStatus::QueuedToDownload
+        | TorrentStatus::QueuedToSeed => "Queued",
+        TorrentStatus::Verifying => "Verify",
+        TorrentStatus::Downloading => "Down",
+        TorrentStatus::Seeding => "Seed",
+    }
+}
+
+/// Size with a one letter unit for narrow columns, e.g. `1.5G` or `300K`.
+pub fn compact_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 || value >= 100.0 {
+        format!("{value:.0}{}", UNITS[unit])
+    } else {
+        format!("{value:.1}{}", UNITS[unit])
+    }
+}
+
+pub fn compact_speed(byte: i64) -> String {
+    compact_size(byte.max(0) as u64) + "/s"
+}
+
 pub fn readble_speed(byte: i64) -> String {
     let byte = byte as f64;
     let kilo_byte = 1024.0;
@@ -246,24 +385,35 @@ pub fn get_conf_dir() -> PathBuf {
     config_dir.join("transent")
 }
 
-pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+/// Directory for state that should survive restarts but is not configuration.
+pub fn get_state_dir() -> PathBuf {
+    let state_dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .expect("Could not find state directory");
+    state_dir.join("transent")
+}
+
+/// Index of the row under the mouse in a table drawn in `area`, with its first
+/// row `top` lines below the area's top edge and scrolled by `offset` rows.
+pub fn row_at(area: Rect, top: u16, offset: usize, column: u16, row: u16) -> Option<usize> {
+    let first = area.y + top;
+    (area.contains(Position::new(column, row)) && row >= first)
+        .then(|| offset + (row - first) as usize)
+}
+
+/// A rect of the given percentages of `r` centred in it, grown to at least
+/// `min` so popups never cut off their content on small terminals.
+pub fn centered_rect(percent_x: u16, percent_y: u16, min: Size, r: Rect) -> Rect {
+    let width = (r.width as u32 * percent_x as u32 / 100) as u16;
+    let height = (r.height as u32 * percent_y as u32 / 100) as u16;
+    let width = width.max(min.width).min(r.width);
+    let height = height.max(min.height).min(r.height);
+    Rect {
+        x: r.x + (r.width - width) / 2,
+        y: r.y + (r.height - height) / 2,
+        width,
+        height,
+    }
 }
 
 pub fn icon_for(entry: &DirEntry) -> &'static str {
@@ -282,3 +432,54 @@ pub fn icon_for(entry: &DirEntry) -> &'static str {
         "📄"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_star_stays_in_one_segment() {
+        let re = path_pattern("*.nfo").unwrap();
+        assert!(re.is_match("Show/Season 1/info.nfo"));
+        assert!(re.is_match("info.nfo"));
+        assert!(!re.is_match("info.nfo.bak"));
+
+        let re = path_pattern("Season 2/*").unwrap();
+        assert!(re.is_match("Show/Season 2/e01.mkv"));
+        assert!(!re.is_match("Show/Season 2/extras/e01.mkv"));
+    }
+
+    #[test]
+    fn glob_double_star_crosses_folders() {
+        let re = path_pattern("Show/**.mkv").unwrap();
+        assert!(re.is_match("Show/Season 2/extras/e01.mkv"));
+        assert!(!re.is_match("Other/e01.mkv"));
+    }
+
+    #[test]
+    fn glob_question_mark_and_literals() {
+        let re = path_pattern("e0?.mkv").unwrap();
+        assert!(re.is_match("Show/e01.mkv"));
+        assert!(!re.is_match("Show/e10.mkv"));
+        // Regex characters in a glob are taken literally.
+        let re = path_pattern("(1).txt").unwrap();
+        assert!(re.is_match("a/(1).txt"));
+        assert!(!re.is_match("a/1.txt"));
+    }
+
+    #[test]
+    fn glob_matches_whole_segments() {
+        let re = path_pattern("a.txt").unwrap();
+        assert!(re.is_match("dir/a.txt"));
+        assert!(!re.is_match("dir/data.txt"));
+    }
+
+    #[test]
+    fn regex_pattern() {
+        let re = path_pattern(r"re:\.(mkv|mp4)$").unwrap();
+        assert!(re.is_match("Show/e01.mkv"));
+        assert!(re.is_match("e01.mp4"));
+        assert!(!re.is_match("e01.srt"));
+        assert!(path_pattern("re:(").is_err());
+    }
+}
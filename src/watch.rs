@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use lava_torrent::torrent::v1::Torrent;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use transmission_rpc::TransClient;
+
+use crate::config::WatchDir;
+use crate::util::expand_path;
+use crate::widgets::torrent_adder::torrent_source;
+
+const SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Files touched more recently than this may still be written by a browser.
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Scans a file is tried on while the daemon can't be reached, before it is
+/// renamed to `*.failed`.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Adds every `.torrent` dropped into the watch dirs, then renames or moves it
+/// so it is only picked up once. Errors are queued in `errors` for the app to
+/// show, this runs while the terminal belongs to the UI.
+pub async fn watch(
+    client: Arc<Mutex<TransClient>>,
+    dirs: Vec<WatchDir>,
+    upload: bool,
+    errors: Arc<Mutex<Vec<String>>>,
+) {
+    let mut attempts: HashMap<PathBuf, u32> = HashMap::new();
+    loop {
+        for dir in &dirs {
+            for path in pending_files(&expand_path(&dir.path)) {
+                let (suffix, error) = match import(&client, dir, &path, upload).await {
+                    Outcome::Added => ("added", None),
+                    Outcome::Failed(suffix, e) => (suffix, Some(e)),
+                    Outcome::Retry(e) => {
+                        let tries = attempts.entry(path.clone()).or_default();
+                        *tries += 1;
+                        if *tries < MAX_ATTEMPTS {
+                            continue;
+                        }
+                        ("failed", Some(e))
+                    }
+                };
+                attempts.remove(&path);
+                let moved = finish(dir, &path, suffix).err();
+                errors.lock().await.extend(error.into_iter().chain(moved));
+            }
+        }
+        sleep(SCAN_INTERVAL).await;
+    }
+}
+
+fn pending_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("torrent"))
+                && fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| SystemTime::now().duration_since(t).ok())
+                    .is_some_and(|age| age >= SETTLE_TIME)
+        })
+        .collect()
+}
+
+enum Outcome {
+    Added,
+    /// Not a torrent file, or rejected by the daemon.
+    Failed(&'static str, String),
+    /// The daemon could not be reached, worth another try.
+    Retry(String),
+}
+
+async fn import(
+    client: &Arc<Mutex<TransClient>>,
+    dir: &WatchDir,
+    path: &Path,
+    upload: bool,
+) -> Outcome {
+    if let Err(e) = Torrent::read_from_file(path) {
+        return Outcome::Failed(
+            "invalid",
+            format!("Invalid torrent {}: {e}", path.display()),
+        );
+    }
+
+    let mut args = match torrent_source(&path.to_string_lossy(), upload) {
+        Ok(args) => args,
+        Err(e) => return Outcome::Failed("failed", e),
+    };
+    // Anything not set here is left to the daemon's defaults.
+    args.download_dir = dir.download_dir.clone();
+    if !dir.labels.is_empty() {
+        args.labels = Some(dir.labels.clone());
+    }
+
+    match client.lock().await.torrent_add(args).await {
+        Ok(resp) if resp.is_ok() => Outcome::Added,
+        Ok(resp) => Outcome::Failed(
+            "failed",
+            format!("Failed to add torrent {}: {}", path.display(), resp.result),
+        ),
+        Err(e) => Outcome::Retry(format!("Failed to add torrent {}: {e}", path.display())),
+    }
+}
+
+/// Moves a processed file out of the way, into `move_to` or by appending `suffix`.
+fn finish(dir: &WatchDir, path: &Path, suffix: &str) -> Result<(), String> {
+    let Some(name) = path.file_name() else {
+        return Ok(());
+    };
+    let target = match &dir.move_to {
+        Some(move_to) if suffix == "added" => {
+            let move_to = expand_path(move_to);
+            let _ = fs::create_dir_all(&move_to);
+            move_to.join(name)
+        }
+        _ => path.with_file_name(format!("{}.{suffix}", name.to_string_lossy())),
+    };
+    // A rename fails across filesystems, fall back to copying.
+    fs::rename(path, &target)
+        .or_else(|_| fs::copy(path, &target).and_then(|_| fs::remove_file(path)))
+        .map_err(|e| format!("Failed to move {}: {e}", path.display()))
+}
@@ -0,0 +1,423 @@
+use std::fs;
+
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Rect, Size},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Padding, Row, Table, TableState},
+};
+use transmission_rpc::types::{Priority, TorrentAddArgs};
+
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    util::{centered_rect, get_state_dir, readable_size},
+    widgets::{
+        file_picker::FilePicker,
+        input::{Input, InputMode},
+    },
+};
+
+const MAX_RECENT_DIRS: usize = 10;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Field {
+    DownloadDir,
+    Paused,
+    BandwidthPriority,
+    Labels,
+    PeerLimit,
+}
+
+const FIELDS: [Field; 5] = [
+    Field::DownloadDir,
+    Field::Paused,
+    Field::BandwidthPriority,
+    Field::Labels,
+    Field::PeerLimit,
+];
+
+/// Options shared by every way of adding a torrent: download dir, paused
+/// start, bandwidth priority, labels and peer limit. Paused and priority stay
+/// `None` until changed, so the daemon's defaults apply.
+pub struct AddOptions {
+    pub download_dir: String,
+    pub paused: Option<bool>,
+    pub bandwidth_priority: Option<Priority>,
+    pub labels: String,
+    pub peer_limit: String,
+    pub is_focused: bool,
+    pub error: Option<String>,
+    /// The download dir can be browsed, only when the daemon shares our disk.
+    local: bool,
+    free_space: Option<(String, Option<i64>)>,
+    recent_dirs: Vec<String>,
+    state: TableState,
+    editing: Option<Field>,
+    input: Input,
+    dir_picker: Option<FilePicker>,
+    recent: Option<TableState>,
+}
+
+impl AddOptions {
+    pub fn new(download_dir: String, local: bool) -> Self {
+        Self {
+            download_dir,
+            paused: None,
+            bandwidth_priority: None,
+            labels: String::new(),
+            peer_limit: String::new(),
+            is_focused: false,
+            error: None,
+            local,
+            free_space: None,
+            recent_dirs: load_recent_dirs(),
+            state: TableState::default().with_selected(Some(0)),
+            editing: None,
+            input: Input::new(),
+            dir_picker: None,
+            recent: None,
+        }
+    }
+
+    /// Directory whose free space has to be fetched from the daemon, if any.
+    pub fn free_space_request(&self) -> Option<String> {
+        let dir = self.download_dir.trim();
+        match &self.free_space {
+            _ if dir.is_empty() => None,
+            Some((checked, _)) if checked == dir => None,
+            _ => Some(dir.to_string()),
+        }
+    }
+
+    pub fn set_free_space(&mut self, dir: String, bytes: Option<i64>) {
+        self.free_space = Some((dir, bytes));
+    }
+
+    /// Free space of the chosen download dir, when the daemon reported it.
+    pub fn free_space(&self) -> Option<i64> {
+        match &self.free_space {
+            Some((dir, bytes)) if dir == self.download_dir.trim() => *bytes,
+            _ => None,
+        }
+    }
+
+    pub fn label_list(&self) -> Vec<String> {
+        self.labels
+            .split(',')
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    }
+
+    pub fn apply(&self, args: &mut TorrentAddArgs) {
+        let dir = self.download_dir.trim();
+        if !dir.is_empty() {
+            args.download_dir = Some(dir.to_string());
+        }
+        args.paused = self.paused;
+        args.bandwidth_priority = self.bandwidth_priority;
+        let labels = self.label_list();
+        if !labels.is_empty() {
+            args.labels = Some(labels);
+        }
+        args.peer_limit = self.peer_limit.trim().parse().ok();
+    }
+
+    /// Moves the chosen download dir to the top of the recent list.
+    pub fn remember_download_dir(&mut self) {
+        let dir = self.download_dir.trim().to_string();
+        if dir.is_empty() {
+            return;
+        }
+        self.recent_dirs.retain(|d| *d != dir);
+        self.recent_dirs.insert(0, dir);
+        self.recent_dirs.truncate(MAX_RECENT_DIRS);
+
+        let path = get_state_dir();
+        let _ = fs::create_dir_all(&path);
+        let _ = fs::write(path.join("recent_dirs"), self.recent_dirs.join("\n"));
+    }
+
+    fn selected_field(&self) -> Field {
+        FIELDS[self.state.selected().unwrap_or(0).min(FIELDS.len() - 1)]
+    }
+
+    /// Context of the keys read while focused, `None` while editing a value.
+    pub fn key_context(&self) -> Option<Context> {
+        if let Some(picker) = &self.dir_picker {
+            picker.key_context()
+        } else if self.recent.is_some() {
+            Some(Context::Menu)
+        } else if self.editing.is_some() {
+            None
+        } else {
+            Some(Context::Options)
+        }
+    }
+
+    pub async fn handler(&mut self, key: KeyEvent, keys: &Keymap) {
+        if let Some(picker) = self.dir_picker.as_mut() {
+            let (close, paths) = picker.handler(key, keys).await;
+            if let Some(path) = paths.into_iter().next() {
+                self.download_dir = path;
+            }
+            if close {
+                self.dir_picker = None;
+            }
+            return;
+        }
+
+        if let Some(state) = self.recent.as_mut() {
+            match keys.action(Context::Menu, &key) {
+                Some(Action::Down) => {
+                    let next = state.selected().map_or(0, |i| i + 1);
+                    state.select(Some(next % self.recent_dirs.len()));
+                }
+                Some(Action::Up) => {
+                    let len = self.recent_dirs.len();
+                    let prev = state.selected().map_or(0, |i| (i + len - 1) % len);
+                    state.select(Some(prev));
+                }
+                Some(Action::Confirm) => {
+                    if let Some(dir) = state.selected().and_then(|i| self.recent_dirs.get(i)) {
+                        self.download_dir = dir.clone();
+                    }
+                    self.recent = None;
+                }
+                Some(Action::Close) => self.recent = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(field) = self.editing {
+            if let Some(value) = self.input.handler(key) {
+                self.set_text(field, value);
+            }
+            if !self.input.is_active {
+                self.editing = None;
+            }
+            return;
+        }
+
+        match keys.action(Context::Options, &key) {
+            Some(Action::Down) => {
+                let next = (self.state.selected().unwrap_or(0) + 1) % FIELDS.len();
+                self.state.select(Some(next));
+            }
+            Some(Action::Up) => {
+                let prev = (self.state.selected().unwrap_or(0) + FIELDS.len() - 1) % FIELDS.len();
+                self.state.select(Some(prev));
+            }
+            Some(Action::Edit) => self.activate(),
+            Some(Action::Browse) if self.local && self.selected_field() == Field::DownloadDir => {
+                let start = if self.download_dir.trim().is_empty() {
+                    "~/".to_string()
+                } else {
+                    self.download_dir.clone()
+                };
+                self.dir_picker = Some(FilePicker::dir_picker(start));
+            }
+            Some(Action::Recent)
+                if self.selected_field() == Field::DownloadDir && !self.recent_dirs.is_empty() =>
+            {
+                self.recent = Some(TableState::default().with_selected(Some(0)));
+            }
+            Some(Action::Close) => self.is_focused = false,
+            _ => {}
+        }
+    }
+
+    fn activate(&mut self) {
+        self.error = None;
+        let field = self.selected_field();
+        let value = match field {
+            Field::Paused => {
+                self.paused = match self.paused {
+                    None => Some(true),
+                    Some(true) => Some(false),
+                    Some(false) => None,
+                };
+                return;
+            }
+            Field::BandwidthPriority => {
+                // Unset leaves the daemon's default.
+                self.bandwidth_priority = match self.bandwidth_priority {
+                    None => Some(Priority::Low),
+                    Some(Priority::Low) => Some(Priority::Normal),
+                    Some(Priority::Normal) => Some(Priority::High),
+                    Some(Priority::High) => None,
+                };
+                return;
+            }
+            Field::DownloadDir => self.download_dir.clone(),
+            Field::Labels => self.labels.clone(),
+            Field::PeerLimit => self.peer_limit.clone(),
+        };
+        self.editing = Some(field);
+        self.input.is_active = true;
+        self.input.input_mode = InputMode::Editing;
+        self.input.set_value(value);
+    }
+
+    fn set_text(&mut self, field: Field, value: String) {
+        let value = value.trim().to_string();
+        match field {
+            Field::DownloadDir => self.download_dir = value,
+            Field::Labels => self.labels = value,
+            Field::PeerLimit => {
+                if value.is_empty() || value.parse::<u16>().is_ok_and(|n| n > 0) {
+                    self.peer_limit = value;
+                } else {
+                    self.error = Some(format!("Invalid peer limit: {value}"));
+                }
+            }
+            Field::Paused | Field::BandwidthPriority => {}
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        let fg = Theme::color(&theme.general.foreground);
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        let dir = match self.free_space() {
+            Some(bytes) => format!(
+                "{}  ({} free)",
+                self.download_dir,
+                readable_size(bytes.max(0) as u64)
+            ),
+            None => self.download_dir.clone(),
+        };
+        let priority = match self.bandwidth_priority {
+            None => "(default)",
+            Some(Priority::Low) => "Low",
+            Some(Priority::Normal) => "Normal",
+            Some(Priority::High) => "High",
+        };
+        let paused = match self.paused {
+            None => "(default)",
+            Some(true) => "Yes",
+            Some(false) => "No",
+        };
+        let or_default = |s: &str| {
+            if s.trim().is_empty() {
+                "(default)".to_string()
+            } else {
+                s.to_string()
+            }
+        };
+
+        let rows = vec![
+            Row::new(["Download dir".to_string(), or_default(&dir)]),
+            Row::new(["Start paused".to_string(), paused.to_string()]),
+            Row::new(["Bandwidth".to_string(), priority.to_string()]),
+            Row::new(["Labels".to_string(), or_default(&self.labels)]),
+            Row::new(["Peer limit".to_string(), or_default(&self.peer_limit)]),
+        ];
+
+        // The owning popup's footer shows the key that focuses the options.
+        let hint = if self.is_focused {
+            let browse = self.selected_field() == Field::DownloadDir;
+            let hints: Vec<(Action, &str)> = [
+                (Action::Edit, "edit"),
+                (Action::Browse, "browse"),
+                (Action::Recent, "recent"),
+                (Action::Close, "back"),
+            ]
+            .into_iter()
+            .filter(|(action, _)| match action {
+                Action::Browse => browse && self.local,
+                Action::Recent => browse,
+                _ => true,
+            })
+            .collect();
+            format!(" Options {} ", keys.hints(Context::Options, &hints, " "))
+        } else {
+            " Options ".to_string()
+        };
+        let title = match &self.error {
+            Some(e) => format!(" {e} "),
+            None => hint,
+        };
+
+        let table = Table::new(rows, [Constraint::Length(14), Constraint::Min(10)])
+            .style(Style::default().fg(fg))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .padding(Padding::horizontal(1))
+                    .title(title)
+                    .border_style(if self.is_focused {
+                        Style::default().fg(fg)
+                    } else {
+                        muted
+                    }),
+            )
+            .row_highlight_style(if self.is_focused {
+                Style::default()
+                    .fg(Theme::color(&theme.table.row_highlight_fg))
+                    .bg(Theme::color(&theme.table.row_highlight_bg))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            });
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+
+        if self.editing.is_some() {
+            let popup = centered_rect(50, 20, Input::POPUP_SIZE, frame.area());
+            frame.render_widget(Clear, popup);
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(" Edit option ")
+                .title_alignment(Alignment::Center)
+                .padding(Padding::horizontal(1));
+            let inner = block.inner(popup);
+            frame.render_widget(block, popup);
+            self.input.render(frame, inner, theme);
+        }
+
+        if let Some(state) = self.recent.as_mut() {
+            let popup = centered_rect(
+                50,
+                40,
+                Size::new(30, self.recent_dirs.len() as u16 + 2),
+                frame.area(),
+            );
+            frame.render_widget(Clear, popup);
+            let rows: Vec<Row> = self
+                .recent_dirs
+                .iter()
+                .map(|d| Row::new([d.clone()]))
+                .collect();
+            let table = Table::new(rows, [Constraint::Percentage(100)])
+                .style(Style::default().fg(fg))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Recent directories ")
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::horizontal(1)),
+                )
+                .row_highlight_style(
+                    Style::default()
+                        .fg(Theme::color(&theme.table.row_highlight_fg))
+                        .bg(Theme::color(&theme.table.row_highlight_bg))
+                        .add_modifier(Modifier::BOLD),
+                );
+            frame.render_stateful_widget(table, popup, state);
+        }
+
+        if let Some(picker) = self.dir_picker.as_mut() {
+            picker.render(frame, theme, keys);
+        }
+    }
+}
+
+fn load_recent_dirs() -> Vec<String> {
+    fs::read_to_string(get_state_dir().join("recent_dirs"))
+        .map(|s| s.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,290 @@
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    util::{centered_rect, readable_size},
+    widgets::{add_options::AddOptions, torrent_adder::torrent_source},
+};
+use crossterm::event::KeyEvent;
+use lava_torrent::torrent::v1::Torrent;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Size},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState},
+};
+use transmission_rpc::types::TorrentAddArgs;
+
+struct BatchEntry {
+    path: String,
+    name: String,
+    size: u64,
+    file_count: usize,
+    info_hash: Option<String>,
+    trackers: Vec<String>,
+    duplicate: bool,
+    error: Option<String>,
+}
+
+/// A torrent of the batch that is ready to be submitted.
+pub struct BatchItem {
+    pub args: TorrentAddArgs,
+    pub hashes: Vec<String>,
+    pub trackers: Vec<String>,
+}
+
+/// Review screen for adding several `.torrent` files with the same options.
+pub struct BatchAdder {
+    entries: Vec<BatchEntry>,
+    state: TableState,
+    upload: bool,
+    pub options: AddOptions,
+    message: Option<String>,
+    low_space_confirmed: bool,
+}
+
+impl BatchAdder {
+    pub fn new(paths: Vec<String>, options: AddOptions, upload: bool, known: &[String]) -> Self {
+        let entries = paths
+            .into_iter()
+            .map(|path| match Torrent::read_from_file(&path) {
+                Ok(t) => {
+                    let info_hash = t.info_hash();
+                    let trackers = match (&t.announce_list, &t.announce) {
+                        (Some(tiers), _) => tiers.iter().flatten().cloned().collect(),
+                        (None, Some(announce)) => vec![announce.clone()],
+                        (None, None) => vec![],
+                    };
+                    BatchEntry {
+                        duplicate: known.iter().any(|h| h.eq_ignore_ascii_case(&info_hash)),
+                        name: t.name.clone(),
+                        size: t.length as u64,
+                        file_count: t.files.as_ref().map_or(1, |f| f.len()),
+                        info_hash: Some(info_hash),
+                        trackers,
+                        error: None,
+                        path,
+                    }
+                }
+                Err(e) => BatchEntry {
+                    name: path.clone(),
+                    size: 0,
+                    file_count: 0,
+                    info_hash: None,
+                    trackers: vec![],
+                    duplicate: false,
+                    error: Some(e.to_string()),
+                    path,
+                },
+            })
+            .collect();
+
+        Self {
+            entries,
+            state: TableState::default().with_selected(Some(0)),
+            upload,
+            options,
+            message: None,
+            low_space_confirmed: false,
+        }
+    }
+
+    fn ready(&self) -> impl Iterator<Item = &BatchEntry> {
+        self.entries
+            .iter()
+            .filter(|e| !e.duplicate && e.error.is_none())
+    }
+
+    fn next(&mut self) {
+        match self.state.selected() {
+            Some(n) if n + 1 >= self.entries.len() => self.state.select(Some(0)),
+            _ => self.state.select_next(),
+        }
+    }
+
+    fn prev(&mut self) {
+        match self.state.selected() {
+            Some(0) => self
+                .state
+                .select(Some(self.entries.len().saturating_sub(1))),
+            _ => self.state.select_previous(),
+        }
+    }
+
+    /// Context of the keys read right now, the options' while they are focused.
+    pub fn key_context(&self) -> Option<Context> {
+        if self.options.is_focused {
+            self.options.key_context()
+        } else {
+            Some(Context::Batch)
+        }
+    }
+
+    pub async fn handler(&mut self, key: KeyEvent, keys: &Keymap) -> (bool, Vec<BatchItem>) {
+        if self.options.is_focused {
+            self.options.handler(key, keys).await;
+            self.low_space_confirmed = false;
+            return (false, vec![]);
+        }
+
+        match keys.action(Context::Batch, &key) {
+            Some(Action::Down) => self.next(),
+            Some(Action::Up) => self.prev(),
+            Some(Action::Remove) => {
+                if let Some(i) = self.state.selected()
+                    && i < self.entries.len()
+                {
+                    self.entries.remove(i);
+                    if i >= self.entries.len() {
+                        self.prev();
+                    }
+                }
+            }
+            Some(Action::Options) => self.options.is_focused = true,
+            Some(Action::Confirm) => {
+                let total: u64 = self.ready().map(|e| e.size).sum();
+                if let Some(free) = self.options.free_space()
+                    && (free.max(0) as u64) < total
+                    && !self.low_space_confirmed
+                {
+                    self.low_space_confirmed = true;
+                    self.message = Some(format!(
+                        "Only {} free for {}, press Enter again to add anyway",
+                        readable_size(free.max(0) as u64),
+                        readable_size(total),
+                    ));
+                    return (false, vec![]);
+                }
+
+                let items: Result<Vec<BatchItem>, String> = self
+                    .ready()
+                    .map(|entry| {
+                        let mut args = torrent_source(&entry.path, self.upload)?;
+                        self.options.apply(&mut args);
+                        Ok(BatchItem {
+                            args,
+                            hashes: entry.info_hash.iter().cloned().collect(),
+                            trackers: entry.trackers.clone(),
+                        })
+                    })
+                    .collect();
+                let items = match items {
+                    Ok(items) => items,
+                    Err(e) => {
+                        self.message = Some(e);
+                        return (false, vec![]);
+                    }
+                };
+                self.options.remember_download_dir();
+                return (true, items);
+            }
+            Some(Action::Close) => return (true, vec![]),
+            _ => {}
+        }
+        (false, vec![])
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(80, 66, Size::new(70, 18), frame.area());
+        frame.render_widget(Clear, area);
+
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // header
+                Constraint::Min(0),    // torrents
+                Constraint::Length(7), // add options
+                Constraint::Length(3), // footer
+            ])
+            .split(area);
+
+        let ready: Vec<&BatchEntry> = self.ready().collect();
+        let header = Block::default()
+            .title(format!(
+                " Add {} of {} torrents — {} ",
+                ready.len(),
+                self.entries.len(),
+                readable_size(ready.iter().map(|e| e.size).sum()),
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .style(fg);
+        frame.render_widget(header, chunks[0]);
+
+        let rows: Vec<Row> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let status = match (&entry.error, entry.duplicate) {
+                    (Some(_), _) => "Unreadable",
+                    (None, true) => "Already added",
+                    (None, false) => "Ready",
+                };
+                let row = Row::new([
+                    Cell::from(entry.name.clone()),
+                    Cell::from(readable_size(entry.size)),
+                    Cell::from(entry.file_count.to_string()),
+                    Cell::from(status),
+                ]);
+                if status == "Ready" {
+                    row
+                } else {
+                    row.style(Style::default().add_modifier(Modifier::DIM))
+                }
+            })
+            .collect();
+
+        let header_row = Row::new(["Name", "Size", "Files", "Status"])
+            .style(fg.add_modifier(Modifier::UNDERLINED | Modifier::BOLD));
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(12),
+                Constraint::Length(7),
+                Constraint::Length(14),
+            ],
+        )
+        .header(header_row)
+        .style(fg)
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+                .padding(Padding::horizontal(1)),
+        )
+        .row_highlight_style(
+            Style::default()
+                .fg(Theme::color(&theme.table.row_highlight_fg))
+                .bg(Theme::color(&theme.table.row_highlight_bg))
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_stateful_widget(table, chunks[1], &mut self.state);
+
+        let hints = match &self.message {
+            Some(message) => Line::from(Span::raw(message.clone())),
+            None => Line::from(keys.hints(
+                Context::Batch,
+                &[
+                    (Action::Down, "down"),
+                    (Action::Up, "up"),
+                    (Action::Remove, "remove"),
+                    (Action::Options, "options"),
+                    (Action::Confirm, "add all"),
+                    (Action::Close, "cancel"),
+                ],
+                "  ",
+            )),
+        };
+        frame.render_widget(
+            Paragraph::new(hints)
+                .block(Block::default().borders(Borders::ALL).style(fg))
+                .alignment(Alignment::Center),
+            chunks[3],
+        );
+
+        self.options.render(frame, chunks[2], theme, keys);
+    }
+}
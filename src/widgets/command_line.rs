@@ -0,0 +1,207 @@
+use std::fs;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::{
+    command::complete,
+    config::Theme,
+    keymap::Context,
+    util::get_state_dir,
+    widgets::input::{Input, InputMode},
+};
+
+const HISTORY_LEN: usize = 100;
+
+/// The `:` prompt in place of the status bar, with history and Tab completion.
+pub struct CommandLine {
+    input: Input,
+    history: Vec<String>,
+    /// Entry shown while stepping through history with Up and Down.
+    history_index: Option<usize>,
+    /// The typed line, restored when stepping past the newest entry.
+    draft: String,
+    /// Completions shown above the prompt after an ambiguous Tab.
+    candidates: Vec<String>,
+    /// Result of the last command, shown until the next key.
+    message: Option<(String, bool)>,
+}
+
+impl CommandLine {
+    pub fn new() -> Self {
+        let history = fs::read_to_string(get_state_dir().join("command_history"))
+            .map(|s| s.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        Self {
+            input: Input::new(),
+            history,
+            history_index: None,
+            draft: String::new(),
+            candidates: vec![],
+            message: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.input.is_active
+    }
+
+    pub fn open(&mut self) {
+        self.input.is_active = true;
+        self.input.input_mode = InputMode::Editing;
+        self.input.set_value(String::new());
+        self.history_index = None;
+        self.candidates.clear();
+        self.message = None;
+    }
+
+    pub fn set_message(&mut self, message: String, is_error: bool) {
+        self.message = Some((message, is_error));
+    }
+
+    pub fn clear_message(&mut self) {
+        self.message = None;
+    }
+
+    /// Returns the line to run once Enter is pressed.
+    pub fn handler(&mut self, key: KeyEvent, contexts: &[Context]) -> Option<String> {
+        if key.code != KeyCode::Tab {
+            self.candidates.clear();
+        }
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => self.close(),
+            KeyCode::Backspace if self.input.input.is_empty() => self.close(),
+            KeyCode::Tab => self.complete(contexts),
+            KeyCode::Up => self.step_history(true),
+            KeyCode::Down => self.step_history(false),
+            _ => {
+                let line = self.input.handler(key)?;
+                self.input.is_active = false;
+                let line = line.trim().to_string();
+                if !line.is_empty() {
+                    self.push_history(&line);
+                }
+                return Some(line);
+            }
+        }
+        None
+    }
+
+    fn close(&mut self) {
+        self.input.is_active = false;
+        self.input.set_value(String::new());
+    }
+
+    fn complete(&mut self, contexts: &[Context]) {
+        let line = self.input.input.clone();
+        let candidates = complete(&line, contexts);
+        match candidates.as_slice() {
+            [] => {}
+            [only] => self.input.set_value(only.clone()),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.as_str(), |common, c| {
+                    let len = common
+                        .char_indices()
+                        .zip(c.chars())
+                        .take_while(|((_, a), b)| a == b)
+                        .last()
+                        .map_or(0, |((i, ch), _)| i + ch.len_utf8());
+                    &common[..len]
+                });
+                if common.len() > line.len() {
+                    self.input.set_value(common.to_string());
+                }
+                // Only the word being completed is listed.
+                let start = line.rfind(' ').map_or(0, |i| i + 1);
+                self.candidates = candidates
+                    .iter()
+                    .map(|c| c[start..].trim_end().to_string())
+                    .collect();
+            }
+        }
+    }
+
+    fn step_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match (self.history_index, older) {
+            (None, true) => {
+                self.draft = self.input.input.clone();
+                Some(self.history.len() - 1)
+            }
+            (None, false) => return,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < self.history.len() => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.history_index = index;
+        let value = match index {
+            Some(i) => self.history[i].clone(),
+            None => self.draft.clone(),
+        };
+        self.input.set_value(value);
+    }
+
+    fn push_history(&mut self, line: &str) {
+        self.history.retain(|l| l != line);
+        self.history.push(line.to_string());
+        if self.history.len() > HISTORY_LEN {
+            self.history.remove(0);
+        }
+        let state_dir = get_state_dir();
+        let _ = fs::create_dir_all(&state_dir);
+        let _ = fs::write(
+            state_dir.join("command_history"),
+            self.history.join("\n") + "\n",
+        );
+    }
+
+    /// Draws the prompt or the last message, returns false when there is
+    /// neither and the status bar should be drawn instead.
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) -> bool {
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        if !self.input.is_active {
+            let Some((message, is_error)) = &self.message else {
+                return false;
+            };
+            let style = if *is_error {
+                Style::default().fg(Theme::color(&theme.details.accent_fg))
+            } else {
+                fg
+            };
+            frame.render_widget(Paragraph::new(message.as_str()).style(style), area);
+            return true;
+        }
+
+        if !self.candidates.is_empty() && area.y > 0 {
+            let above = Rect {
+                y: area.y - 1,
+                height: 1,
+                ..area
+            };
+            let spans: Vec<Span> = self
+                .candidates
+                .iter()
+                .flat_map(|c| [Span::styled(c.clone(), muted), Span::raw("  ")])
+                .collect();
+            frame.render_widget(Clear, above);
+            frame.render_widget(
+                Paragraph::new(Line::from(spans))
+                    .style(Style::default().bg(Theme::color(&theme.general.background))),
+                above,
+            );
+        }
+        self.input.render_line(frame, area, ":", fg);
+        true
+    }
+}
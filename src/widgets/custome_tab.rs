@@ -1,5 +1,6 @@
 use crate::config::Theme;
 use ratatui::Frame;
+use ratatui::layout::Position;
 use ratatui::layout::Rect;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
@@ -11,6 +12,8 @@ pub struct CustomeTabs {
     titles: Vec<String>,
     selected: usize,
     pub is_focused: bool,
+    /// Where the tabs were last drawn, for mouse clicks.
+    pub area: Rect,
 }
 
 impl CustomeTabs {
@@ -19,6 +22,7 @@ impl CustomeTabs {
             titles,
             selected: 0,
             is_focused,
+            area: Rect::default(),
         }
     }
 
@@ -37,11 +41,39 @@ impl CustomeTabs {
         }
     }
 
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.titles.len() {
+            self.selected = index;
+        }
+    }
+
+    /// The tab under a mouse position. Titles are padded by one space on each
+    /// side and separated by a one column divider.
+    pub fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let mut x = self.area.x + 1;
+        for (i, title) in self.titles.iter().enumerate() {
+            let width = title.chars().count() as u16 + 2;
+            if column >= x && column < x + width {
+                return Some(i);
+            }
+            x += width + 1;
+        }
+        None
+    }
+
     pub fn selected_tab(&self) -> String {
         self.titles[self.selected].clone()
     }
 
-    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.area = area;
         let base_style = Style::default()
             .fg(Theme::color(&theme.tabs.inactive_fg))
             .bg(Theme::color(&theme.tabs.inactive_bg));
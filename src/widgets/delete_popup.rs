@@ -1,5 +1,6 @@
 use ratatui::Frame;
 use ratatui::layout::Alignment;
+use ratatui::layout::Size;
 use ratatui::style::Style;
 use ratatui::widgets::Block;
 use ratatui::widgets::BorderType;
@@ -8,7 +9,11 @@ use ratatui::widgets::Clear;
 use ratatui::widgets::Paragraph;
 use transmission_rpc::types::Id;
 
-use crate::{config::Theme, util::centered_rect};
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    util::centered_rect,
+};
 
 pub struct DeletePopup {
     pub id: Id,
@@ -25,8 +30,8 @@ impl DeletePopup {
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
-        let area = centered_rect(50, 50, frame.area());
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(50, 50, Size::new(24, 7), frame.area());
 
         frame.render_widget(Clear, area);
 
@@ -36,7 +41,12 @@ impl DeletePopup {
             "Delete Without Data"
         };
 
-        let paragraph = Paragraph::new(format!("\n\n{}\n\n[Y]es    [N]o", text))
+        let answers = keys.hints(
+            Context::Confirm,
+            &[(Action::Confirm, "yes"), (Action::Close, "no")],
+            "    ",
+        );
+        let paragraph = Paragraph::new(format!("\n\n{text}\n\n{answers}"))
             .alignment(Alignment::Center)
             .style(Style::default().fg(Theme::color(&theme.general.foreground)))
             .block(
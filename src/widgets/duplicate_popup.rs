@@ -0,0 +1,90 @@
+use crate::config::Theme;
+use crate::util::centered_rect;
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Size;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::widgets::Block;
+use ratatui::widgets::BorderType;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+use ratatui::widgets::TableState;
+use transmission_rpc::types::Id;
+
+static ACTIONS_STR: [&str; 3] = ["Jump to existing torrent", "Merge trackers", "Cancel"];
+
+/// Shown instead of adding a torrent whose info-hash is already in the daemon.
+pub struct DuplicatePopup {
+    pub id: Id,
+    pub name: String,
+    pub hash: String,
+    /// Trackers of the torrent that was about to be added.
+    pub trackers: Vec<String>,
+    state: TableState,
+}
+
+impl DuplicatePopup {
+    pub fn new(id: Id, name: String, hash: String, trackers: Vec<String>) -> Self {
+        Self {
+            id,
+            name,
+            hash,
+            trackers,
+            state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        match self.state.selected() {
+            Some(n) if n >= ACTIONS_STR.len() - 1 => self.state.select(Some(0)),
+            _ => self.state.select_next(),
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        match self.state.selected() {
+            Some(0) => self.state.select(Some(ACTIONS_STR.len() - 1)),
+            _ => self.state.select_previous(),
+        }
+    }
+
+    pub fn get_selected(&self) -> Option<&str> {
+        self.state.selected().map(|i| ACTIONS_STR[i])
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
+        let area = centered_rect(
+            50,
+            30,
+            Size::new(30, ACTIONS_STR.len() as u16 + 4),
+            frame.area(),
+        );
+        frame.render_widget(Clear, area);
+
+        let rows: Vec<Row> = ACTIONS_STR
+            .iter()
+            .map(|action| Row::new([*action]))
+            .collect();
+
+        let table = Table::new(rows, [Constraint::Percentage(100)])
+            .style(Style::default().fg(Theme::color(&theme.general.foreground)))
+            .row_highlight_style(
+                Style::default()
+                    .fg(Theme::color(&theme.table.row_highlight_fg))
+                    .bg(Theme::color(&theme.table.row_highlight_bg))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .padding(ratatui::widgets::Padding::uniform(1))
+                    .title(format!(" Already added: {} ", self.name)),
+            );
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
@@ -1,23 +1,30 @@
 use crate::config::Theme;
+use crate::keymap::{Action, Context, Keymap};
 use crate::util::calculate_match_score;
 use crate::util::centered_rect;
+use crate::util::complete_path;
 use crate::util::expand_path;
 use crate::util::fuzzy_match;
 use crate::util::get_entries;
 use crate::util::icon_for;
+use crate::util::row_at;
 use crate::widgets::input::Input;
 use crate::widgets::input::InputMode;
+use crate::widgets::torrent_preview::TorrentPreview;
 
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
-use crossterm::event::KeyModifiers;
 use ratatui::Frame;
 use ratatui::layout::Alignment;
 use ratatui::layout::Constraint;
 use ratatui::layout::Constraint::Percentage;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::layout::Size;
 use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
 use ratatui::widgets::Clear;
@@ -27,64 +34,196 @@ use ratatui::widgets::Table;
 use ratatui::widgets::TableState;
 use std::fs::DirEntry;
 use std::path::Path;
+use std::sync::Arc;
+
+/// Upper bound for a recursive search so huge trees stay responsive.
+const MAX_RECURSIVE_ENTRIES: usize = 10_000;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum PickMode {
+    /// `.torrent` files, several can be marked for a batch add.
+    Torrents,
+    /// Directories only.
+    Dirs,
+    /// Any file or directory.
+    Any,
+}
 
 pub struct FilePicker {
     path: String,
-    entries: Vec<DirEntry>,
+    /// Everything in the current directory, or below it for a recursive search.
+    listing: Vec<Arc<DirEntry>>,
+    /// The listing narrowed and ranked by the filter.
+    entries: Vec<Arc<DirEntry>>,
+    filter: String,
+    recursive: bool,
     prev_states: Vec<TableState>,
     state: TableState,
 
     show_hidden: bool,
+    mode: PickMode,
+    /// `.torrent` files marked for a batch add, kept across directories.
+    marked: Vec<String>,
+    /// Metadata of the highlighted `.torrent`, when the info pane is open.
+    preview: Option<Option<TorrentPreview>>,
+    bookmarks: Vec<String>,
+    /// Selection in the bookmark list while it is open.
+    bookmark_state: Option<TableState>,
+    /// The input holds a path to jump to rather than a search.
+    jumping: bool,
     input: Input,
+    /// Where the list was last drawn, for mouse clicks.
+    list_area: Rect,
 }
 
 impl FilePicker {
     pub fn new(path: String, show_hidden: bool) -> Self {
-        let real_path = expand_path(path.clone());
-        FilePicker {
+        let real_path = expand_path(path);
+        let mut picker = FilePicker {
             path: real_path.display().to_string(),
-            entries: get_entries(path.clone(), show_hidden),
+            listing: vec![],
+            entries: vec![],
+            filter: String::new(),
+            recursive: false,
             prev_states: vec![],
             state: TableState::default(),
             input: Input::new(),
             show_hidden,
-        }
+            mode: PickMode::Torrents,
+            marked: vec![],
+            preview: None,
+            bookmarks: vec![],
+            bookmark_state: None,
+            jumping: false,
+            list_area: Rect::default(),
+        };
+        picker.reload();
+        picker
     }
 
-    fn select_best_match(&mut self) {
-        if !self.input.is_active {
-            return;
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn with_bookmarks(mut self, bookmarks: Vec<String>) -> Self {
+        self.bookmarks = bookmarks;
+        self
+    }
+
+    pub fn dir_picker(path: String) -> Self {
+        let mut picker = Self::new(path, false);
+        picker.mode = PickMode::Dirs;
+        picker.reload();
+        picker
+    }
+
+    /// Picker returning any file or directory, e.g. the data of a new torrent.
+    pub fn source_picker(path: String) -> Self {
+        let mut picker = Self::new(path, false);
+        picker.mode = PickMode::Any;
+        picker.reload();
+        picker
+    }
+
+    fn load_entries(&self, path: &str) -> Vec<DirEntry> {
+        let torrents_only = self.mode == PickMode::Torrents;
+        let mut entries = get_entries(path.to_string(), self.show_hidden, torrents_only);
+        if self.recursive {
+            // Breadth first, symlinked directories are not followed.
+            let mut i = 0;
+            while i < entries.len() && entries.len() < MAX_RECURSIVE_ENTRIES {
+                if entries[i].file_type().is_ok_and(|t| t.is_dir()) {
+                    let dir = entries[i].path().display().to_string();
+                    entries.extend(get_entries(dir, self.show_hidden, torrents_only));
+                }
+                i += 1;
+            }
         }
+        if self.mode == PickMode::Dirs {
+            entries.retain(|e| e.path().is_dir());
+        }
+        entries
+    }
+
+    /// Re-reads the directory, or the tree below it for a recursive search,
+    /// and applies the filter again.
+    fn reload(&mut self) {
+        self.listing = self
+            .load_entries(&self.path)
+            .into_iter()
+            .map(Arc::new)
+            .collect();
+        self.apply_filter();
+    }
+
+    fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.recursive = false;
+    }
 
-        let input = self.input.input.to_lowercase();
+    /// Path of an entry relative to the current directory.
+    fn display_name(&self, entry: &DirEntry) -> String {
+        match entry.path().strip_prefix(&self.path) {
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => entry.file_name().to_string_lossy().to_string(),
+        }
+    }
 
-        if input.is_empty() {
-            self.state.select(Some(0));
+    /// Keeps only the entries matching the filter, best match first.
+    fn apply_filter(&mut self) {
+        let query = self.filter.to_lowercase();
+        if query.is_empty() {
+            self.entries = self.listing.clone();
             return;
         }
 
-        // Find the best matching entry based on fuzzy match
-        let mut best_index: Option<usize> = None;
-        let mut best_score = 0;
+        let mut matches: Vec<(usize, Arc<DirEntry>)> = self
+            .listing
+            .iter()
+            .filter_map(|entry| {
+                let name = self.display_name(entry).to_lowercase();
+                fuzzy_match(&name, &query)
+                    .then(|| (calculate_match_score(&name, &query), entry.clone()))
+            })
+            .collect();
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.entries = matches.into_iter().map(|(_, entry)| entry).collect();
+    }
 
-        for (index, entry) in self.entries.iter().enumerate() {
-            let name = entry.file_name().to_string_lossy().to_lowercase();
-            if fuzzy_match(&name, &input) {
-                // Score based on how early the match starts and match length
-                let score = calculate_match_score(&name, &input);
-                if score > best_score {
-                    best_score = score;
-                    best_index = Some(index);
-                }
-            }
+    /// Selects the entry under a mouse position, returns whether there was one.
+    pub fn select_at(&mut self, column: u16, row: u16) -> bool {
+        if self.bookmark_state.is_some() || self.input.is_active {
+            return false;
+        }
+        // Inside the border and the one line top padding.
+        let hit = row_at(self.list_area, 2, self.state.offset(), column, row)
+            .filter(|i| *i < self.entries.len());
+        if hit.is_some() {
+            self.state.select(hit);
         }
+        hit.is_some()
+    }
 
-        if let Some(index) = best_index {
-            self.state.select(Some(index));
+    /// Wheel scrolling, ignored while the bookmark list or an input is open.
+    pub fn scroll(&mut self, down: bool) {
+        if self.bookmark_state.is_some() || self.input.is_active {
+            return;
         }
+        if down {
+            self.select_next();
+        } else {
+            self.select_prev();
+        }
+    }
+
+    fn select_first(&mut self) {
+        self.state.select((!self.entries.is_empty()).then_some(0));
     }
 
     fn select_next(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
         match self.state.selected() {
             Some(n) if n >= self.entries.len() - 1 => self.state.select(Some(0)),
             _ => self.state.select_next(),
@@ -92,6 +231,9 @@ impl FilePicker {
     }
 
     fn select_prev(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
         match self.state.selected() {
             Some(0) => self.state.select(Some(self.entries.len() - 1)),
             _ => self.state.select_previous(),
@@ -104,7 +246,8 @@ impl FilePicker {
 
         if let Some(parent) = path.parent() {
             self.path = parent.display().to_string();
-            self.entries = get_entries(self.path.clone(), false);
+            self.clear_filter();
+            self.reload();
 
             if !self.prev_states.is_empty() {
                 self.state = self.prev_states.pop().unwrap();
@@ -119,80 +262,342 @@ impl FilePicker {
         }
     }
 
-    async fn select_entry(&mut self) -> (bool, Option<String>) {
+    /// Opens a directory, or the parent of a file with the file highlighted.
+    fn jump_to(&mut self, path: &str) {
+        let real = expand_path(path.trim());
+        let (dir, file) = if real.is_dir() {
+            (real, None)
+        } else {
+            match real.parent() {
+                Some(parent) if parent.is_dir() => (parent.to_path_buf(), Some(real)),
+                _ => return,
+            }
+        };
+        self.prev_states.clear();
+        self.path = dir.display().to_string();
+        self.clear_filter();
+        self.reload();
+        let index = file
+            .and_then(|f| self.entries.iter().position(|e| e.path() == f))
+            .or((!self.entries.is_empty()).then_some(0));
+        self.state.select(index);
+    }
+
+    fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        let selected = self
+            .state
+            .selected()
+            .and_then(|n| self.entries.get(n))
+            .map(|e| e.path());
+        self.reload();
+        let index = selected
+            .and_then(|p| self.entries.iter().position(|e| e.path() == p))
+            .or((!self.entries.is_empty()).then_some(0));
+        self.state.select(index);
+    }
+
+    fn toggle_mark(&mut self) {
+        let Some(entry) = self.state.selected().and_then(|n| self.entries.get(n)) else {
+            return;
+        };
+        if !entry.path().display().to_string().ends_with(".torrent") {
+            return;
+        }
+        let Some(path) = real_path(entry) else {
+            return;
+        };
+        if let Some(pos) = self.marked.iter().position(|p| *p == path) {
+            self.marked.remove(pos);
+        } else {
+            self.marked.push(path);
+        }
+        self.select_next();
+    }
+
+    /// Every marked file plus all `.torrent` files currently listed.
+    fn take_folder(&mut self) -> Vec<String> {
+        let mut paths = std::mem::take(&mut self.marked);
+        for entry in &self.entries {
+            if !entry.path().display().to_string().ends_with(".torrent") {
+                continue;
+            }
+            if let Some(path) = real_path(entry)
+                && !paths.contains(&path)
+            {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Opens the highlighted folder or picks the highlighted entry, as the
+    /// open key does.
+    pub async fn select_entry(&mut self) -> (bool, Vec<String>) {
         self.input.input = "".to_string();
         match self.state.selected() {
             Some(n) if n < self.entries.len() => {
-                let path = self.entries[n]
-                    .path()
-                    .canonicalize()
-                    .unwrap()
-                    .display()
-                    .to_string();
+                // A broken symlink has nothing to open or pick.
+                let Some(path) = real_path(&self.entries[n]) else {
+                    return (false, vec![]);
+                };
+
+                if self.mode == PickMode::Any && !self.entries[n].path().is_dir() {
+                    return (true, vec![path]);
+                }
 
                 if path.ends_with(".torrent") {
-                    return (true, Some(path));
+                    let mut paths = std::mem::take(&mut self.marked);
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                    return (true, paths);
                 }
 
                 self.prev_states.push(self.state.clone());
                 self.path = path;
-                self.entries = get_entries(self.path.to_string(), self.show_hidden);
-                match self.entries.len() {
-                    0 => self.state.select(None),
-                    _ => self.state.select(Some(0)),
-                }
+                self.clear_filter();
+                self.reload();
+                self.select_first();
             }
             None => {}
             _ => {}
         }
-        (false, None)
+        (false, vec![])
     }
 
-    pub async fn handler(&mut self, key: KeyEvent) -> (bool, Option<String>) {
+    /// Context of the keys read right now, `None` while typing a path or search.
+    pub fn key_context(&self) -> Option<Context> {
         if self.input.is_active {
-            self.input.handler(key);
-            self.select_best_match();
-            return (false, None);
+            None
+        } else if self.bookmark_state.is_some() {
+            Some(Context::Menu)
+        } else {
+            Some(Context::Picker)
         }
+    }
 
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
-            KeyCode::Char('k') | KeyCode::Up => self.select_prev(),
-            KeyCode::Char('h') | KeyCode::Left => self.go_back(),
-            KeyCode::Char('l') | KeyCode::Enter | KeyCode::Right => {
-                return self.select_entry().await;
+    /// Returns whether the picker should close and the chosen paths: `.torrent`
+    /// files, or a single path for directory and source pickers.
+    pub async fn handler(&mut self, key: KeyEvent, keys: &Keymap) -> (bool, Vec<String>) {
+        if self.input.is_active && self.jumping {
+            if key.code == KeyCode::Tab {
+                let completed = complete_path(&self.input.input);
+                self.input.set_value(completed);
+            } else if let Some(path) = self.input.handler(key) {
+                self.jump_to(&path);
             }
-            KeyCode::Char('/') => {
+            self.jumping = self.input.is_active;
+            return (false, vec![]);
+        }
+
+        if self.input.is_active {
+            if key.code == KeyCode::Tab {
+                self.recursive = !self.recursive;
+                self.reload();
+            } else if let Some(query) = self.input.handler(key) {
+                // Enter keeps the list narrowed while browsing it.
+                self.filter = query;
+                self.apply_filter();
+            } else if self.input.is_active {
+                self.filter = self.input.input.clone();
+                self.apply_filter();
+            } else {
+                self.clear_filter();
+                self.reload();
+            }
+            self.select_first();
+            return (false, vec![]);
+        }
+
+        if let Some(state) = self.bookmark_state.as_mut() {
+            match keys.action(Context::Menu, &key) {
+                Some(Action::Down) => {
+                    let next = state.selected().map_or(0, |i| i + 1);
+                    state.select(Some(next % self.bookmarks.len()));
+                }
+                Some(Action::Up) => {
+                    let len = self.bookmarks.len();
+                    let prev = state.selected().map_or(0, |i| (i + len - 1) % len);
+                    state.select(Some(prev));
+                }
+                Some(Action::Confirm) => {
+                    if let Some(dir) = state.selected().and_then(|i| self.bookmarks.get(i)) {
+                        let dir = dir.clone();
+                        self.jump_to(&dir);
+                    }
+                    self.bookmark_state = None;
+                }
+                Some(Action::Close) => self.bookmark_state = None,
+                _ => {}
+            }
+            return (false, vec![]);
+        }
+
+        match keys.action(Context::Picker, &key) {
+            Some(Action::Down) => self.select_next(),
+            Some(Action::Up) => self.select_prev(),
+            Some(Action::Back) => self.go_back(),
+            Some(Action::Open) => return self.select_entry().await,
+            Some(Action::Mark) if self.mode != PickMode::Torrents => {
+                if let Some(entry) = self.state.selected().and_then(|n| self.entries.get(n)) {
+                    return (true, vec![entry.path().display().to_string()]);
+                }
+            }
+            Some(Action::PickCurrent) if self.mode != PickMode::Torrents => {
+                return (true, vec![self.path.clone()]);
+            }
+            Some(Action::Mark) => self.toggle_mark(),
+            Some(Action::AddFolder) if self.mode == PickMode::Torrents => {
+                let paths = self.take_folder();
+                if !paths.is_empty() {
+                    return (true, paths);
+                }
+            }
+            Some(Action::Preview) if self.mode == PickMode::Torrents => {
+                self.preview = match self.preview {
+                    Some(_) => None,
+                    None => Some(None),
+                };
+            }
+            Some(Action::ToggleHidden) => self.toggle_hidden(),
+            Some(Action::Bookmarks) if !self.bookmarks.is_empty() => {
+                self.bookmark_state = Some(TableState::default().with_selected(Some(0)));
+            }
+            Some(Action::GoTo) => {
+                self.jumping = true;
                 self.input.is_active = true;
                 self.input.input_mode = InputMode::Editing;
-                self.input.reset_cursor();
+                let mut path = self.path.clone();
+                if !path.ends_with('/') {
+                    path.push('/');
+                }
+                self.input.set_value(path);
             }
-            KeyCode::Char('q') | KeyCode::Esc => return (true, None),
-            KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                return (true, None);
+            Some(Action::Filter) => {
+                self.input.is_active = true;
+                self.input.input_mode = InputMode::Editing;
+                self.input.set_value(self.filter.clone());
+            }
+            Some(Action::Close) if !self.filter.is_empty() => {
+                self.clear_filter();
+                self.reload();
+                self.select_first();
             }
+            Some(Action::Close) => return (true, vec![]),
             _ => {}
         }
-        (false, None)
+        (false, vec![])
     }
 
-    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
-        let area = centered_rect(50, 75, frame.area());
+    /// Loads the preview of the highlighted entry if it is not the cached one.
+    fn update_preview(&mut self) {
+        let path = self
+            .state
+            .selected()
+            .and_then(|n| self.entries.get(n))
+            .map(|e| e.path().display().to_string())
+            .filter(|p| p.ends_with(".torrent"));
+        let Some(preview) = self.preview.as_mut() else {
+            return;
+        };
+        match path {
+            Some(path) if preview.as_ref().is_some_and(|p| p.path == path) => {}
+            Some(path) => *preview = Some(TorrentPreview::load(path)),
+            None => *preview = None,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        self.update_preview();
+        let area = if self.preview.is_some() {
+            centered_rect(80, 75, Size::new(70, 20), frame.area())
+        } else {
+            centered_rect(50, 75, Size::new(40, 20), frame.area())
+        };
+        let (area, info_area) = if self.preview.is_some() {
+            let split = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Percentage(50), Percentage(50)])
+                .split(area);
+            (split[0], Some(split[1]))
+        } else {
+            (area, None)
+        };
 
+        let matched = Style::default()
+            .fg(Theme::color(&theme.details.accent_fg))
+            .add_modifier(ratatui::style::Modifier::BOLD);
+        let query = self.filter.to_lowercase();
         let rows: Vec<Row> = self
             .entries
             .iter()
             .map(|entry| {
-                Row::new([icon_for(entry).to_string() + entry.file_name().to_str().unwrap()])
+                let marked = !self.marked.is_empty()
+                    && real_path(entry).is_some_and(|p| self.marked.contains(&p));
+                let mark = if marked { "● " } else { "" };
+                let mut spans = vec![Span::raw(mark.to_string() + icon_for(entry))];
+                spans.extend(highlight_matches(
+                    &self.display_name(entry),
+                    &query,
+                    matched,
+                ));
+                Row::new([Line::from(spans)])
             })
             .collect();
         let widths = [Constraint::Percentage(100)];
 
-        let block = Block::new()
-            .title(self.path.clone())
+        let hints = if self.jumping {
+            " [Tab complete] [Enter go] ".to_string()
+        } else if self.input.is_active {
+            " [Tab recursive] [Enter keep filter] ".to_string()
+        } else {
+            let hints: &[(Action, &str)] = if self.mode != PickMode::Torrents {
+                &[
+                    (Action::Mark, "pick"),
+                    (Action::PickCurrent, "pick current"),
+                    (Action::GoTo, "go to"),
+                    (Action::ToggleHidden, "hidden"),
+                ]
+            } else if !self.marked.is_empty() {
+                &[
+                    (Action::Open, "add"),
+                    (Action::AddFolder, "add folder"),
+                    (Action::GoTo, "go to"),
+                    (Action::ToggleHidden, "hidden"),
+                ]
+            } else {
+                &[
+                    (Action::Mark, "mark"),
+                    (Action::AddFolder, "add folder"),
+                    (Action::Preview, "info"),
+                    (Action::GoTo, "go to"),
+                    (Action::ToggleHidden, "hidden"),
+                ]
+            };
+            let hints = keys.hints(Context::Picker, hints, " ");
+            if self.mode == PickMode::Torrents && !self.marked.is_empty() {
+                format!(" {} marked {hints} ", self.marked.len())
+            } else {
+                format!(" {hints} ")
+            }
+        };
+        let mut block = Block::new()
+            .title(match (self.filter.is_empty(), self.recursive) {
+                (true, false) => self.path.clone(),
+                (true, true) => format!("{}  (recursive)", self.path),
+                (false, false) => format!("{}  /{}", self.path, self.filter),
+                (false, true) => format!("{}  /{} (recursive)", self.path, self.filter),
+            })
+            .title_bottom(hints)
             .padding(Padding::new(2, 2, 1, 1))
             .borders(Borders::all())
             .title_alignment(Alignment::Center);
+        if !self.bookmarks.is_empty()
+            && let Some(hint) = keys.hint(Context::Picker, Action::Bookmarks, "bookmarks")
+        {
+            block = block.title_bottom(format!(" {hint} "));
+        }
 
         let table = Table::new(rows, widths)
             .style(Theme::color(&theme.general.foreground))
@@ -209,6 +614,7 @@ impl FilePicker {
             .constraints(vec![Percentage(80), Percentage(20)])
             .split(area);
 
+        self.list_area = chunks[0];
         if self.input.is_active {
             frame.render_widget(Clear, area);
             frame.render_stateful_widget(table, chunks[0], &mut self.state);
@@ -217,5 +623,99 @@ impl FilePicker {
             frame.render_widget(Clear, chunks[0]);
             frame.render_stateful_widget(table, chunks[0], &mut self.state);
         }
+
+        if let Some(state) = self.bookmark_state.as_mut() {
+            let popup = centered_rect(
+                40,
+                40,
+                Size::new(30, self.bookmarks.len() as u16 + 2),
+                frame.area(),
+            );
+            frame.render_widget(Clear, popup);
+            let rows: Vec<Row> = self
+                .bookmarks
+                .iter()
+                .map(|b| Row::new([b.clone()]))
+                .collect();
+            let table = Table::new(rows, [Constraint::Percentage(100)])
+                .style(Theme::color(&theme.general.foreground))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Bookmarks ")
+                        .title_alignment(Alignment::Center)
+                        .padding(Padding::horizontal(1)),
+                )
+                .row_highlight_style(
+                    Style::default()
+                        .fg(Theme::color(&theme.table.row_highlight_fg))
+                        .bg(Theme::color(&theme.table.row_highlight_bg))
+                        .add_modifier(ratatui::style::Modifier::BOLD),
+                );
+            frame.render_stateful_widget(table, popup, state);
+        }
+
+        if let Some(info_area) = info_area {
+            let info_area = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Percentage(80), Percentage(20)])
+                .split(info_area)[0];
+            frame.render_widget(Clear, info_area);
+            match self.preview.as_ref().and_then(|p| p.as_ref()) {
+                Some(preview) => preview.render(frame, info_area, theme),
+                None => frame.render_widget(
+                    Block::default()
+                        .title(" Info ")
+                        .borders(Borders::ALL)
+                        .style(Theme::color(&theme.general.foreground)),
+                    info_area,
+                ),
+            }
+        }
+    }
+}
+
+/// The entry's path with symlinks resolved, so a file reached two ways is
+/// marked and queued once. `None` for a broken symlink.
+fn real_path(entry: &DirEntry) -> Option<String> {
+    entry
+        .path()
+        .canonicalize()
+        .ok()
+        .map(|p| p.display().to_string())
+}
+
+/// Splits `name` into spans with the characters matched by the fuzzy `query` styled.
+fn highlight_matches(name: &str, query: &str, style: Style) -> Vec<Span<'static>> {
+    let mut query_chars = query.chars().peekable();
+    let mut spans: Vec<Span> = vec![];
+    let mut current = String::new();
+    let mut current_matched = false;
+
+    for c in name.chars() {
+        let is_match = query_chars
+            .peek()
+            .is_some_and(|q| c.to_lowercase().eq(q.to_lowercase()));
+        if is_match {
+            query_chars.next();
+        }
+        if is_match != current_matched && !current.is_empty() {
+            let text = std::mem::take(&mut current);
+            spans.push(if current_matched {
+                Span::styled(text, style)
+            } else {
+                Span::raw(text)
+            });
+        }
+        current_matched = is_match;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(if current_matched {
+            Span::styled(current, style)
+        } else {
+            Span::raw(current)
+        });
     }
+    spans
 }
@@ -1,4 +1,3 @@
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::Frame;
 use ratatui::layout::Constraint;
 use ratatui::layout::Rect;
@@ -11,7 +10,10 @@ use transmission_rpc::types::TorrentSetArgs;
 use transmission_rpc::types::{File, Priority};
 
 use crate::config::Theme;
+use crate::file_tree::FileTree;
+use crate::keymap::Action;
 use crate::util::readable_size;
+use crate::util::row_at;
 
 const BAR_WIDTH: usize = 20;
 const WIDTHS: [Constraint; 5] = [
@@ -26,7 +28,10 @@ pub struct FilesTable {
     pub files: Vec<File>,
     pub priorities: Vec<Priority>,
     pub wanted: Vec<bool>,
+    tree: FileTree,
     state: TableState,
+    /// Where the table was last drawn, for mouse clicks.
+    pub area: Rect,
 }
 
 impl FilesTable {
@@ -35,22 +40,56 @@ impl FilesTable {
             files: vec![],
             priorities: vec![],
             wanted: vec![],
+            tree: FileTree::new::<&str>(&[]),
             state: TableState::default(),
+            area: Rect::default(),
         }
     }
 
+    /// Replaces the file data, rebuilding the folder tree only when the file
+    /// list itself changed so collapsed folders survive poll refreshes.
+    pub fn set_data(&mut self, files: Vec<File>, priorities: Vec<Priority>, wanted: Vec<bool>) {
+        let same_files = files.len() == self.files.len()
+            && files.iter().zip(&self.files).all(|(a, b)| a.name == b.name);
+        if !same_files {
+            let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+            self.tree = FileTree::new(&names);
+            self.state.select(None);
+        }
+        self.files = files;
+        self.priorities = priorities;
+        self.wanted = wanted;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_data(vec![], vec![], vec![]);
+    }
+
+    fn selected_node(&self) -> Option<usize> {
+        let visible = self.tree.visible();
+        self.state.selected().and_then(|i| visible.get(i).copied())
+    }
+
     // ── Navigation ────────────────────────────────────────────────────────────
 
     pub fn select_next(&mut self) {
+        let len = self.tree.visible().len();
+        if len == 0 {
+            return;
+        }
         match self.state.selected() {
-            Some(n) if n >= self.files.len() - 1 => self.state.select(Some(0)),
+            Some(n) if n >= len - 1 => self.state.select(Some(0)),
             _ => self.state.select_next(),
         }
     }
 
     pub fn select_prev(&mut self) {
+        let len = self.tree.visible().len();
+        if len == 0 {
+            return;
+        }
         match self.state.selected() {
-            Some(0) => self.state.select(Some(self.files.len() - 1)),
+            Some(0) => self.state.select(Some(len - 1)),
             _ => self.state.select_previous(),
         }
     }
@@ -62,64 +101,92 @@ impl FilesTable {
     }
 
     pub fn select_last(&mut self) {
-        if !self.files.is_empty() {
-            self.state.select(Some(self.files.len() - 1));
+        let len = self.tree.visible().len();
+        if len > 0 {
+            self.state.select(Some(len - 1));
+        }
+    }
+
+    /// Selects the row under a mouse position.
+    pub fn select_at(&mut self, column: u16, row: u16) {
+        if let Some(i) = row_at(self.area, 1, self.state.offset(), column, row)
+            && i < self.tree.visible().len()
+        {
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn toggle_collapsed(&mut self) {
+        if let Some(node) = self.selected_node() {
+            self.tree.toggle_collapsed(node);
         }
     }
 
     // ── Actions ───────────────────────────────────────────────────────────────
 
+    /// Toggles a file, or every file below a folder, in a single request.
     pub fn toggle_wanted(&mut self) -> Option<TorrentSetArgs> {
-        if let Some(i) = self.state.selected()
-            && let Some(w) = self.wanted.get(i)
-        {
-            let tsa = TorrentSetArgs::new();
-            if *w {
-                return Some(tsa.files_unwanted(vec![i]));
-            } else {
-                return Some(tsa.files_wanted(vec![i]));
-            }
+        let node = &self.tree.nodes[self.selected_node()?];
+        let files = node.files.clone();
+        if files.is_empty() {
+            return None;
+        }
+
+        let all_wanted = files
+            .iter()
+            .all(|i| self.wanted.get(*i).copied().unwrap_or(true));
+        let tsa = TorrentSetArgs::new();
+        if all_wanted {
+            Some(tsa.files_unwanted(files))
+        } else {
+            Some(tsa.files_wanted(files))
         }
-        None
     }
 
+    /// Cycles the priority of a file, or of every file below a folder.
+    /// Folders with mixed priorities are reset to normal.
     pub fn cycle_priority(&mut self) -> Option<TorrentSetArgs> {
-        if let Some(i) = self.state.selected()
-            && let Some(p) = self.priorities.get_mut(i)
-        {
-            let tsa = TorrentSetArgs::new();
-            let tsa = match p {
-                Priority::Low => tsa.priority_normal(vec![i]),
-                Priority::Normal => tsa.priority_high(vec![i]),
-                Priority::High => tsa.priority_low(vec![i]),
-            };
-            return Some(tsa);
+        let node = &self.tree.nodes[self.selected_node()?];
+        let files = node.files.clone();
+        if files.is_empty() {
+            return None;
         }
-        None
+
+        let tsa = TorrentSetArgs::new();
+        let tsa = match common_priority(&files, &self.priorities) {
+            Some(Priority::Low) | None => tsa.priority_normal(files),
+            Some(Priority::Normal) => tsa.priority_high(files),
+            Some(Priority::High) => tsa.priority_low(files),
+        };
+        Some(tsa)
     }
 
-    // ── Key handler ───────────────────────────────────────────────────────────
+    // ── Action handler ───────────────────────────────────────────────────────────
 
-    pub fn handler(&mut self, key: KeyEvent) -> Option<TorrentSetArgs> {
-        match key.code {
-            KeyCode::Down | KeyCode::Char('j') => {
+    pub fn handler(&mut self, action: Action) -> Option<TorrentSetArgs> {
+        match action {
+            Action::Down => {
                 self.select_next();
                 None
             }
-            KeyCode::Up | KeyCode::Char('k') => {
+            Action::Up => {
                 self.select_prev();
                 None
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::First => {
                 self.select_first();
                 None
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Last => {
                 self.select_last();
                 None
             }
-            KeyCode::Char(' ') => self.toggle_wanted(),
-            KeyCode::Char('p') => self.cycle_priority(),
+            Action::Fold => {
+                self.toggle_collapsed();
+                None
+            }
+            Action::ToggleWanted => self.toggle_wanted(),
+            Action::CyclePriority => self.cycle_priority(),
             _ => None,
         }
     }
@@ -127,6 +194,7 @@ impl FilesTable {
     // ── Render ────────────────────────────────────────────────────────────────
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.area = area;
         let header =
             Row::new(["  ", "Name", "Size", "Progress", "Priority"]).style(Style::new().bold());
         let selected_bg = Theme::color(&theme.table.row_highlight_bg);
@@ -134,32 +202,62 @@ impl FilesTable {
         let selected = self.state.selected();
 
         let rows: Vec<Row> = self
-            .files
-            .iter()
+            .tree
+            .visible()
+            .into_iter()
             .enumerate()
-            .map(|(i, file)| {
-                let wanted = self.wanted.get(i).copied().unwrap_or(true);
-                let checkbox = if wanted { "" } else { "" };
-                let size = readable_size(file.length as u64);
-                let priority = self
-                    .priorities
-                    .get(i)
-                    .map(|p| match p {
-                        Priority::Low => "Low",
-                        Priority::Normal => "Normal",
-                        Priority::High => "High",
-                    })
-                    .unwrap_or("N/A");
+            .map(|(i, node_index)| {
+                let node = &self.tree.nodes[node_index];
+                let wanted_count = node
+                    .files
+                    .iter()
+                    .filter(|f| self.wanted.get(**f).copied().unwrap_or(true))
+                    .count();
+                let checkbox = match wanted_count {
+                    0 => "",
+                    n if n == node.files.len() => "",
+                    _ => "",
+                };
+
+                let length: u64 = node
+                    .files
+                    .iter()
+                    .filter_map(|f| self.files.get(*f))
+                    .map(|f| f.length as u64)
+                    .sum();
+                let completed: u64 = node
+                    .files
+                    .iter()
+                    .filter_map(|f| self.files.get(*f))
+                    .map(|f| f.bytes_completed as u64)
+                    .sum();
+                let size = readable_size(length);
+                let priority = match common_priority(&node.files, &self.priorities) {
+                    Some(Priority::Low) => "Low",
+                    Some(Priority::Normal) => "Normal",
+                    Some(Priority::High) => "High",
+                    None if node.is_dir => "Mixed",
+                    None => "N/A",
+                };
 
                 let progress_cell = Cell::from(progress_bar(
-                    file.bytes_completed as u64,
-                    file.length as u64,
+                    completed,
+                    length,
                     Theme::color(&theme.progress_bar.filled),
                     Theme::color(&theme.progress_bar.empty),
                 ));
 
-                let name: Vec<&str> = file.name.split_terminator('/').collect();
-                let name = name.last().unwrap().to_string();
+                let indent = "  ".repeat(node.depth);
+                let name = if node.is_dir {
+                    let arrow = if self.tree.is_collapsed(node_index) {
+                        "▸"
+                    } else {
+                        "▾"
+                    };
+                    format!("{indent}{arrow} {}/", node.name)
+                } else {
+                    format!("{indent}  {}", node.name)
+                };
 
                 let row = match selected {
                     Some(n) if n == i => Row::new([
@@ -178,7 +276,7 @@ impl FilesTable {
                     ]),
                 };
 
-                if wanted {
+                if wanted_count > 0 {
                     row
                 } else {
                     row.style(Style::default().dim())
@@ -197,6 +295,13 @@ impl FilesTable {
     }
 }
 
+/// The priority shared by all `files`, or `None` when they differ.
+fn common_priority(files: &[usize], priorities: &[Priority]) -> Option<Priority> {
+    let mut iter = files.iter().map(|f| priorities.get(*f).copied());
+    let first = iter.next()??;
+    iter.all(|p| p == Some(first)).then_some(first)
+}
+
 // ── Progress bar ──────────────────────────────────────────────────────────────
 //
 // Returns a `Line` made of two `Span`s:
@@ -0,0 +1,138 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+};
+
+/// Keys of the focused pane or popup, read from the keymap so remapped keys
+/// show up as configured.
+pub struct Help {
+    /// Section title with `(keys, description)` rows.
+    sections: Vec<(&'static str, Vec<(String, &'static str)>)>,
+    /// Contexts shown, their help key closes the overlay again.
+    contexts: Vec<Context>,
+    scroll: u16,
+}
+
+impl Help {
+    pub fn new(keymap: &Keymap, contexts: &[(&'static str, Context)]) -> Self {
+        let sections = contexts
+            .iter()
+            .map(|(title, context)| {
+                let rows = keymap
+                    .bindings(*context)
+                    .map(|(description, keys)| {
+                        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+                        (keys.join(" "), description)
+                    })
+                    .collect();
+                (*title, rows)
+            })
+            .collect();
+        Self {
+            sections,
+            contexts: contexts.iter().map(|(_, context)| *context).collect(),
+            scroll: 0,
+        }
+    }
+
+    /// Returns whether the overlay should close.
+    pub fn handler(&mut self, key: KeyEvent, keys: &Keymap) -> bool {
+        match keys.action(Context::Help, &key) {
+            Some(Action::Down) => self.scroll = self.scroll.saturating_add(1),
+            Some(Action::Up) => self.scroll = self.scroll.saturating_sub(1),
+            Some(Action::Close) => return true,
+            _ => {
+                return self
+                    .contexts
+                    .iter()
+                    .chain([&Context::Global])
+                    .any(|context| keys.action(*context, &key) == Some(Action::Help));
+            }
+        }
+        false
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let accent = Style::default().fg(Theme::color(&theme.details.accent_fg));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        let key_width = self
+            .sections
+            .iter()
+            .flat_map(|(_, rows)| rows.iter().map(|(keys, _)| keys.chars().count()))
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = vec![];
+        for (title, rows) in &self.sections {
+            if !lines.is_empty() {
+                lines.push(Line::raw(""));
+            }
+            lines.push(Line::from(Span::styled(
+                *title,
+                fg.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            )));
+            for (keys, description) in rows {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{keys:>key_width$}"), accent),
+                    Span::raw("  "),
+                    Span::styled(*description, fg),
+                ]));
+            }
+        }
+
+        let content_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let max_scroll = (lines.len() as u16).saturating_sub(1);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let screen = frame.area();
+        // Wide enough for the bottom title as well.
+        let width = (content_width + 6).max(28).min(screen.width);
+        let height = (lines.len() as u16 + 4).min(screen.height);
+        let area = Rect {
+            x: screen.x + (screen.width - width) / 2,
+            y: screen.y + (screen.height - height) / 2,
+            width,
+            height,
+        };
+        frame.render_widget(Clear, area);
+
+        let scroll = match (
+            keys.key(Context::Help, Action::Down),
+            keys.key(Context::Help, Action::Up),
+        ) {
+            (Some(down), Some(up)) => Some(format!("[{down}/{up} scroll]")),
+            _ => None,
+        };
+        let hints: Vec<String> = scroll
+            .into_iter()
+            .chain(keys.hint(Context::Help, Action::Close, "close"))
+            .collect();
+        let block = Block::default()
+            .title(" Keys ")
+            .title_bottom(Line::from(Span::styled(
+                format!(" {} ", hints.join(" ")),
+                muted,
+            )))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(fg)
+            .padding(Padding::new(2, 2, 1, 1))
+            .style(fg);
+        frame.render_widget(
+            Paragraph::new(lines).block(block).scroll((self.scroll, 0)),
+            area,
+        );
+    }
+}
@@ -9,6 +9,7 @@ use ratatui::layout::Direction;
 use ratatui::layout::Layout;
 use ratatui::layout::Position;
 use ratatui::layout::Rect;
+use ratatui::layout::Size;
 
 use ratatui::style::Style;
 use ratatui::style::Stylize;
@@ -38,6 +39,9 @@ pub enum InputMode {
 }
 
 impl Input {
+    /// Smallest popup holding the help line and the input box, with a border.
+    pub const POPUP_SIZE: Size = Size::new(40, 6);
+
     pub const fn new() -> Self {
         Self {
             input: String::new(),
@@ -134,6 +138,12 @@ impl Input {
         self.character_index = 0;
     }
 
+    /// Replaces the content and puts the cursor after it.
+    pub fn set_value(&mut self, value: String) {
+        self.input = value;
+        self.move_cursor_to_end();
+    }
+
     pub fn handler(&mut self, key: KeyEvent) -> Option<String> {
         match self.input_mode {
             InputMode::Normal => match key.code {
@@ -213,6 +223,26 @@ impl Input {
         }
     }
 
+    /// One line with `prefix` in front of the value, for prompts without the
+    /// mode hint and border.
+    pub fn render_line(&self, frame: &mut Frame, area: Rect, prefix: &str, style: Style) {
+        let prefix_width = prefix.chars().count();
+        let visible_width = (area.width as usize).saturating_sub(prefix_width + 1);
+        let scroll_offset = self.character_index.saturating_sub(visible_width);
+        let visible_input: String = self
+            .input
+            .chars()
+            .skip(scroll_offset)
+            .take(visible_width)
+            .collect();
+        frame.render_widget(
+            Paragraph::new(format!("{prefix}{visible_input}")).style(style),
+            area,
+        );
+        let cursor_x = prefix_width + self.character_index - scroll_offset;
+        frame.set_cursor_position(Position::new(area.x + cursor_x as u16, area.y));
+    }
+
     pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
@@ -1,52 +1,236 @@
 use crossterm::event::KeyEvent;
 use ratatui::{
     Frame,
-    layout::Alignment,
-    widgets::{Block, Borders, Clear, Padding},
+    layout::{Alignment, Constraint, Direction, Layout, Size},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
 };
 use transmission_rpc::types::TorrentAddArgs;
 
-use crate::{config::Theme, util::centered_rect, widgets::input::Input};
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    magnet_uri::MagnetUri,
+    util::{centered_rect, readable_size},
+    widgets::{
+        add_options::AddOptions,
+        input::{Input, InputMode},
+    },
+};
 
 pub struct Magnet {
     input: Input,
+    /// The parsed link once the input was accepted.
+    link: Option<(String, MagnetUri)>,
+    error: Option<String>,
+    pub options: AddOptions,
+    low_space_confirmed: bool,
 }
 
 impl Magnet {
-    pub fn new() -> Self {
+    pub fn new(options: AddOptions) -> Self {
         let mut input = Input::new();
         input.is_active = true;
-        Self { input }
+        Self {
+            input,
+            link: None,
+            error: None,
+            options,
+            low_space_confirmed: false,
+        }
     }
-    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
-        let area = centered_rect(50, 40, frame.area());
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(60, 60, Size::new(56, 16), frame.area());
         frame.render_widget(Clear, area);
 
         let block = Block::new()
             .title("Add torrent from Magnet Link")
-            .padding(Padding::new(1, 1, 3, 3))
+            .padding(Padding::new(1, 1, 1, 1))
             .borders(Borders::all())
             .title_alignment(Alignment::Center);
 
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
 
-        self.input.render(frame, inner_area, theme);
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        let Some((_, magnet)) = &self.link else {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(4), Constraint::Min(1)])
+                .split(inner_area);
+            self.input.render(frame, chunks[0], theme);
+            if let Some(error) = &self.error {
+                frame.render_widget(
+                    Paragraph::new(error.as_str())
+                        .style(fg.add_modifier(Modifier::BOLD))
+                        .wrap(Wrap { trim: true }),
+                    chunks[1],
+                );
+            }
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(4),
+                Constraint::Length(7),
+                Constraint::Length(1),
+            ])
+            .split(inner_area);
+
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{label:<12}"), muted),
+                Span::styled(value, fg),
+            ])
+        };
+        let unknown = || "-".to_string();
+        let mut lines = vec![
+            field("Name", magnet.name.clone().unwrap_or_else(unknown)),
+            field(
+                "Info-hash",
+                magnet.info_hash_v1.clone().unwrap_or_else(unknown),
+            ),
+        ];
+        if let Some(v2) = &magnet.info_hash_v2 {
+            lines.push(field("Info-hash v2", v2.clone()));
+        }
+        lines.push(field(
+            "Size",
+            magnet.length.map(readable_size).unwrap_or_else(unknown),
+        ));
+        lines.push(field("Trackers", magnet.trackers.len().to_string()));
+        for tracker in &magnet.trackers {
+            lines.push(Line::from(Span::styled(format!("  {tracker}"), muted)));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        self.options.render(frame, chunks[1], theme, keys);
+
+        let footer = match &self.error {
+            Some(error) => error.clone(),
+            None => keys.hints(
+                Context::Magnet,
+                &[
+                    (Action::Confirm, "add"),
+                    (Action::Options, "options"),
+                    (Action::EditLink, "edit link"),
+                    (Action::Close, "cancel"),
+                ],
+                "  ",
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(footer)
+                .style(fg)
+                .alignment(Alignment::Center),
+            chunks[2],
+        );
     }
 
-    pub fn handler(&mut self, key: KeyEvent) -> (bool, Option<TorrentAddArgs>) {
-        let arg = if let Some(s) = self.input.handler(key) {
-            let arg = TorrentAddArgs {
-                filename: Some(s),
-                files_unwanted: None,
-                ..Default::default()
-            };
-            Some(arg)
-        } else {
+    /// Context of the keys read right now, `None` while typing the link.
+    pub fn key_context(&self) -> Option<Context> {
+        if self.link.is_none() {
             None
-        };
+        } else if self.options.is_focused {
+            self.options.key_context()
+        } else {
+            Some(Context::Magnet)
+        }
+    }
+
+    pub async fn handler(
+        &mut self,
+        key: KeyEvent,
+        keys: &Keymap,
+    ) -> (bool, Option<TorrentAddArgs>) {
+        if self.link.is_none() {
+            if let Some(s) = self.input.handler(key) {
+                match MagnetUri::parse(&s) {
+                    Ok(magnet) => {
+                        self.error = None;
+                        self.link = Some((s, magnet));
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                        self.edit_link(s);
+                    }
+                }
+            }
+            return (!self.input.is_active && self.link.is_none(), None);
+        }
 
-        (!self.input.is_active, arg)
+        if self.options.is_focused {
+            self.options.handler(key, keys).await;
+            self.low_space_confirmed = false;
+            return (false, None);
+        }
+
+        match keys.action(Context::Magnet, &key) {
+            Some(Action::Confirm) => {
+                let Some((link, magnet)) = &self.link else {
+                    return (false, None);
+                };
+                if let (Some(length), Some(free)) = (magnet.length, self.options.free_space())
+                    && (free.max(0) as u64) < length
+                    && !self.low_space_confirmed
+                {
+                    self.low_space_confirmed = true;
+                    self.error = Some(format!(
+                        "Only {} free, press Enter again to add anyway",
+                        readable_size(free.max(0) as u64)
+                    ));
+                    return (false, None);
+                }
+
+                let mut arg = TorrentAddArgs {
+                    filename: Some(link.trim().to_string()),
+                    files_unwanted: None,
+                    ..Default::default()
+                };
+                self.options.apply(&mut arg);
+                self.options.remember_download_dir();
+                (true, Some(arg))
+            }
+            Some(Action::Options) => {
+                self.options.is_focused = true;
+                (false, None)
+            }
+            Some(Action::EditLink) => {
+                if let Some((link, _)) = self.link.take() {
+                    self.edit_link(link);
+                }
+                (false, None)
+            }
+            Some(Action::Close) => (true, None),
+            _ => (false, None),
+        }
     }
-}
 
+    /// Info-hashes of the parsed link, v1 first.
+    pub fn info_hashes(&self) -> Vec<String> {
+        self.link
+            .iter()
+            .flat_map(|(_, m)| [m.info_hash_v1.clone(), m.info_hash_v2.clone()])
+            .flatten()
+            .collect()
+    }
+
+    pub fn trackers(&self) -> Vec<String> {
+        self.link
+            .as_ref()
+            .map(|(_, m)| m.trackers.clone())
+            .unwrap_or_default()
+    }
+
+    fn edit_link(&mut self, link: String) {
+        self.input.is_active = true;
+        self.input.input_mode = InputMode::Editing;
+        self.input.set_value(link);
+    }
+}
@@ -1,11 +1,22 @@
+pub mod add_options;
+pub mod batch_adder;
+pub mod command_line;
 pub mod custome_tab;
 pub mod delete_popup;
+pub mod duplicate_popup;
 pub mod file_picker;
 pub mod files_table;
+pub mod help;
 pub mod input;
 pub mod magnet;
 pub mod peers_table;
+pub mod settings;
+pub mod stats_view;
+pub mod status_bar;
+pub mod theme_picker;
 pub mod torrent_actions;
 pub mod torrent_adder;
+pub mod torrent_creator;
 pub mod torrent_details;
+pub mod torrent_preview;
 pub mod torrent_table;
@@ -11,43 +11,92 @@ use ratatui::widgets::TableState;
 use transmission_rpc::types::Peer;
 
 use crate::config::Theme;
+use crate::geoip::GeoIp;
 use crate::util::readble_speed;
+use crate::util::row_at;
 
 pub struct PeersTable {
     pub peers: Vec<Peer>,
     state: TableState,
+    geoip: Option<GeoIp>,
+    /// Where the table was last drawn, for mouse clicks.
+    pub area: Rect,
 }
 
 impl PeersTable {
-    pub fn new() -> Self {
+    pub fn new(geoip: Option<GeoIp>) -> Self {
         Self {
             peers: vec![],
             state: TableState::default(),
+            geoip,
+            area: Rect::default(),
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.peers.is_empty() {
+            let next = self
+                .state
+                .selected()
+                .map_or(0, |i| (i + 1).min(self.peers.len() - 1));
+            self.state.select(Some(next));
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.peers.is_empty() {
+            let prev = self.state.selected().map_or(0, |i| i.saturating_sub(1));
+            self.state.select(Some(prev));
+        }
+    }
+
+    /// Selects the row under a mouse position.
+    pub fn select_at(&mut self, column: u16, row: u16) {
+        if let Some(i) = row_at(self.area, 1, self.state.offset(), column, row)
+            && i < self.peers.len()
+        {
+            self.state.select(Some(i));
         }
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
-        let header = Row::new(["Adress", "Client", "Download Speed", "Upload Speed"])
-            .style(Style::new().bold());
+        self.area = area;
+        let mut header = vec!["Adress", "Client", "Download Speed", "Upload Speed"];
+        let mut widths = vec![
+            Constraint::Percentage(30),
+            Constraint::Percentage(40),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+        ];
+        if self.geoip.is_some() {
+            header.insert(1, "Country");
+            widths = vec![
+                Constraint::Percentage(25),
+                Constraint::Percentage(10),
+                Constraint::Percentage(35),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+            ];
+        }
+        let header = Row::new(header).style(Style::new().bold());
 
         let rows: Vec<Row> = self
             .peers
             .iter()
             .map(|peer| {
-                Row::new([
+                let mut cells = vec![
                     peer.address.to_string().clone(),
                     peer.client_name.clone(),
                     readble_speed((peer.rate_to_client) as i64),
                     readble_speed((peer.rate_to_peer) as i64),
-                ])
+                ];
+                if let Some(geoip) = self.geoip.as_mut() {
+                    let country = geoip.country(peer.address).unwrap_or("-".to_string());
+                    cells.insert(1, country);
+                }
+                Row::new(cells)
             })
             .collect();
-        let widths = [
-            Constraint::Percentage(30),
-            Constraint::Percentage(40),
-            Constraint::Percentage(15),
-            Constraint::Percentage(15),
-        ];
 
         let block = Block::default().padding(Padding::new(1, 1, 0, 0));
         let table = Table::new(rows, widths)
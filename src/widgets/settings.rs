@@ -0,0 +1,500 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Size},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Row, Table, TableState},
+};
+use serde_json::{Map, Value};
+use transmission_rpc::types::SessionSetArgs;
+
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    util::centered_rect,
+    widgets::input::{Input, InputMode},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Bool,
+    /// Integer within an inclusive range.
+    Number(i64, i64),
+    Ratio,
+    Text,
+    Encryption,
+    /// Minutes after midnight, edited as `HH:MM`.
+    Time,
+    /// Bitmask of weekdays, Sunday first.
+    Days,
+}
+
+struct Field {
+    section: &'static str,
+    key: &'static str,
+    label: &'static str,
+    kind: Kind,
+    unit: &'static str,
+}
+
+const fn field(
+    section: &'static str,
+    key: &'static str,
+    label: &'static str,
+    kind: Kind,
+    unit: &'static str,
+) -> Field {
+    Field {
+        section,
+        key,
+        label,
+        kind,
+        unit,
+    }
+}
+
+const SPEED: i64 = 10_000_000;
+#[rustfmt::skip]
+const FIELDS: [Field; 31] = [
+    field("Speed limits", "speed-limit-down-enabled", "Limit download", Kind::Bool, ""),
+    field("Speed limits", "speed-limit-down", "Download limit", Kind::Number(0, SPEED), "KB/s"),
+    field("Speed limits", "speed-limit-up-enabled", "Limit upload", Kind::Bool, ""),
+    field("Speed limits", "speed-limit-up", "Upload limit", Kind::Number(0, SPEED), "KB/s"),
+    field("Alternative speed", "alt-speed-enabled", "Enabled", Kind::Bool, ""),
+    field("Alternative speed", "alt-speed-down", "Download limit", Kind::Number(0, SPEED), "KB/s"),
+    field("Alternative speed", "alt-speed-up", "Upload limit", Kind::Number(0, SPEED), "KB/s"),
+    field("Alternative speed", "alt-speed-time-enabled", "Scheduled", Kind::Bool, ""),
+    field("Alternative speed", "alt-speed-time-begin", "From", Kind::Time, ""),
+    field("Alternative speed", "alt-speed-time-end", "To", Kind::Time, ""),
+    field("Alternative speed", "alt-speed-time-day", "Days", Kind::Days, ""),
+    field("Downloads", "download-dir", "Download dir", Kind::Text, ""),
+    field("Downloads", "incomplete-dir-enabled", "Use incomplete dir", Kind::Bool, ""),
+    field("Downloads", "incomplete-dir", "Incomplete dir", Kind::Text, ""),
+    field("Downloads", "start-added-torrents", "Start when added", Kind::Bool, ""),
+    field("Network", "peer-port", "Peer port", Kind::Number(1, 65535), ""),
+    field("Network", "encryption", "Encryption", Kind::Encryption, ""),
+    field("Network", "dht-enabled", "DHT", Kind::Bool, ""),
+    field("Network", "pex-enabled", "PEX", Kind::Bool, ""),
+    field("Network", "lpd-enabled", "Local peer discovery", Kind::Bool, ""),
+    field("Network", "utp-enabled", "uTP", Kind::Bool, ""),
+    field("Queue", "download-queue-enabled", "Limit downloads", Kind::Bool, ""),
+    field("Queue", "download-queue-size", "Active downloads", Kind::Number(1, 1000), ""),
+    field("Queue", "seed-queue-enabled", "Limit seeds", Kind::Bool, ""),
+    field("Queue", "seed-queue-size", "Active seeds", Kind::Number(1, 1000), ""),
+    field("Queue", "queue-stalled-enabled", "Skip stalled", Kind::Bool, ""),
+    field("Queue", "queue-stalled-minutes", "Stalled after", Kind::Number(1, 10_000), "min"),
+    field("Seeding", "seedRatioLimited", "Stop at ratio", Kind::Bool, ""),
+    field("Seeding", "seedRatioLimit", "Ratio", Kind::Ratio, ""),
+    field("Seeding", "idle-seeding-limit-enabled", "Stop when idle", Kind::Bool, ""),
+    field("Seeding", "idle-seeding-limit", "Idle for", Kind::Number(1, 100_000), "min"),
+];
+
+const ENCRYPTION: [&str; 3] = ["required", "preferred", "tolerated"];
+const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Daemon-wide settings from `session-get`, saved with `session-set`.
+pub struct Settings {
+    values: Map<String, Value>,
+    /// Edited values that were not saved yet.
+    changes: Map<String, Value>,
+    selected: usize,
+    state: TableState,
+    editing: bool,
+    input: Input,
+    message: Option<String>,
+    discard_confirmed: bool,
+}
+
+impl Settings {
+    pub fn new(values: Result<Map<String, Value>, String>) -> Self {
+        let (values, message) = match values {
+            Ok(values) => (values, None),
+            Err(e) => (Map::new(), Some(format!("Failed to load settings: {e}"))),
+        };
+        Self {
+            values,
+            changes: Map::new(),
+            selected: 0,
+            state: TableState::default(),
+            editing: false,
+            input: Input::new(),
+            message,
+            discard_confirmed: false,
+        }
+    }
+
+    fn value(&self, key: &str) -> Option<&Value> {
+        self.changes.get(key).or_else(|| self.values.get(key))
+    }
+
+    fn set(&mut self, key: &str, value: Value) {
+        if self.values.get(key) == Some(&value) {
+            self.changes.remove(key);
+        } else {
+            self.changes.insert(key.to_string(), value);
+        }
+        self.discard_confirmed = false;
+    }
+
+    /// Called with the outcome of the `session-set` built from the last save.
+    pub fn save_result(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                let changes = std::mem::take(&mut self.changes);
+                self.values.extend(changes);
+                self.message = Some("Settings saved".to_string());
+            }
+            Err(e) => self.message = Some(format!("Failed to save settings: {e}")),
+        }
+    }
+
+    /// Context of the keys read right now, `None` while editing a value.
+    pub fn key_context(&self) -> Option<Context> {
+        (!self.editing).then_some(Context::Settings)
+    }
+
+    /// Returns whether to close and the arguments to send when saving.
+    pub fn handler(&mut self, key: KeyEvent, keys: &Keymap) -> (bool, Option<SessionSetArgs>) {
+        if self.editing {
+            if let Some(text) = self.input.handler(key) {
+                let field = &FIELDS[self.selected];
+                match parse(field.kind, &text) {
+                    Ok(value) => self.set(field.key, value),
+                    Err(e) => self.message = Some(format!("{}: {e}", field.label)),
+                }
+            }
+            self.editing = self.input.is_active;
+            return (false, None);
+        }
+
+        match keys.action(Context::Settings, &key) {
+            Some(Action::Down) => {
+                self.selected = (self.selected + 1) % FIELDS.len();
+            }
+            Some(Action::Up) => {
+                self.selected = (self.selected + FIELDS.len() - 1) % FIELDS.len();
+            }
+            Some(Action::Edit) => self.activate(),
+            Some(Action::Undo) => {
+                self.changes.remove(FIELDS[self.selected].key);
+            }
+            Some(Action::Save) if !self.changes.is_empty() => {
+                return (false, Some(session_set_args(&self.changes)));
+            }
+            Some(Action::Close) => return self.close(),
+            _ => {}
+        }
+        (false, None)
+    }
+
+    fn close(&mut self) -> (bool, Option<SessionSetArgs>) {
+        if self.changes.is_empty() || self.discard_confirmed {
+            return (true, None);
+        }
+        self.discard_confirmed = true;
+        self.message = Some(format!(
+            "{} unsaved changes, press Esc again to discard",
+            self.changes.len()
+        ));
+        (false, None)
+    }
+
+    fn activate(&mut self) {
+        self.message = None;
+        let field = &FIELDS[self.selected];
+        if !self.values.contains_key(field.key) {
+            self.message = Some(format!("{} is not supported by this daemon", field.label));
+            return;
+        }
+        match field.kind {
+            Kind::Bool => {
+                let on = self
+                    .value(field.key)
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                self.set(field.key, Value::Bool(!on));
+            }
+            Kind::Encryption => {
+                let current = self.value(field.key).and_then(Value::as_str).unwrap_or("");
+                let next = ENCRYPTION
+                    .iter()
+                    .position(|e| *e == current)
+                    .map_or(0, |i| (i + 1) % ENCRYPTION.len());
+                self.set(field.key, Value::from(ENCRYPTION[next]));
+            }
+            _ => {
+                let text = self.value(field.key).map(|v| display(field.kind, v));
+                self.editing = true;
+                self.input.is_active = true;
+                self.input.input_mode = InputMode::Editing;
+                self.input.set_value(text.unwrap_or_default());
+            }
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(60, 80, Size::new(58, 12), frame.area());
+        frame.render_widget(Clear, area);
+
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+        let accent = Style::default()
+            .fg(Theme::color(&theme.details.accent_fg))
+            .add_modifier(Modifier::BOLD);
+
+        let block = Block::default()
+            .title(" Settings ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .padding(Padding::new(1, 1, 1, 0))
+            .style(fg);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+
+        let mut rows = vec![];
+        let mut selected_row = 0;
+        let mut section = "";
+        for (i, field) in FIELDS.iter().enumerate() {
+            if field.section != section {
+                if !section.is_empty() {
+                    rows.push(Row::new([""]));
+                }
+                section = field.section;
+                rows.push(Row::new([section]).style(accent));
+            }
+            if i == self.selected {
+                selected_row = rows.len();
+            }
+
+            let value = match self.value(field.key) {
+                Some(v) => {
+                    let mut text = display(field.kind, v);
+                    if !field.unit.is_empty() {
+                        text = format!("{text} {}", field.unit);
+                    }
+                    text
+                }
+                None => "-".to_string(),
+            };
+            let changed = if self.changes.contains_key(field.key) {
+                "*"
+            } else {
+                " "
+            };
+            let style = if self.values.contains_key(field.key) {
+                fg
+            } else {
+                muted
+            };
+            rows.push(
+                Row::new([format!("  {}", field.label), changed.to_string(), value]).style(style),
+            );
+        }
+        self.state.select(Some(selected_row));
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Length(1),
+                Constraint::Min(10),
+            ],
+        )
+        .row_highlight_style(
+            Style::default()
+                .fg(Theme::color(&theme.table.row_highlight_fg))
+                .bg(Theme::color(&theme.table.row_highlight_bg))
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_stateful_widget(table, chunks[0], &mut self.state);
+
+        let footer = match &self.message {
+            Some(message) => Paragraph::new(message.as_str()).style(fg),
+            None if self.changes.is_empty() => Paragraph::new(keys.hints(
+                Context::Settings,
+                &[(Action::Edit, "edit"), (Action::Close, "close")],
+                " ",
+            ))
+            .style(muted),
+            None => {
+                let save = format!("save {} changes", self.changes.len());
+                Paragraph::new(keys.hints(
+                    Context::Settings,
+                    &[
+                        (Action::Edit, "edit"),
+                        (Action::Undo, "undo"),
+                        (Action::Save, &save),
+                        (Action::Close, "close"),
+                    ],
+                    " ",
+                ))
+                .style(muted)
+            }
+        };
+        frame.render_widget(footer.alignment(Alignment::Center), chunks[1]);
+
+        if self.editing {
+            let popup = centered_rect(50, 20, Input::POPUP_SIZE, frame.area());
+            frame.render_widget(Clear, popup);
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} ", FIELDS[self.selected].label))
+                .title_alignment(Alignment::Center)
+                .padding(Padding::horizontal(1));
+            let inner = block.inner(popup);
+            frame.render_widget(block, popup);
+            self.input.render(frame, inner, theme);
+        }
+    }
+}
+
+fn display(kind: Kind, value: &Value) -> String {
+    match (kind, value) {
+        (Kind::Bool, Value::Bool(b)) => if *b { "Yes" } else { "No" }.to_string(),
+        (Kind::Time, Value::Number(n)) => {
+            let minutes = n.as_i64().unwrap_or(0);
+            format!("{:02}:{:02}", minutes / 60, minutes % 60)
+        }
+        (Kind::Days, Value::Number(n)) => match n.as_i64().unwrap_or(0) {
+            127 => "Every day".to_string(),
+            62 => "Weekdays".to_string(),
+            65 => "Weekends".to_string(),
+            mask => DAYS
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, d)| *d)
+                .collect::<Vec<_>>()
+                .join(","),
+        },
+        (_, Value::String(s)) => s.clone(),
+        (_, v) => v.to_string(),
+    }
+}
+
+fn parse(kind: Kind, text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    match kind {
+        Kind::Number(min, max) => match text.parse::<i64>() {
+            Ok(n) if (min..=max).contains(&n) => Ok(Value::from(n)),
+            _ => Err(format!("expected a whole number from {min} to {max}")),
+        },
+        Kind::Ratio => match text.parse::<f64>() {
+            Ok(r) if r >= 0.0 && r.is_finite() => Ok(Value::from(r)),
+            _ => Err("expected a ratio like 2.0".to_string()),
+        },
+        Kind::Text if text.is_empty() => Err("must not be empty".to_string()),
+        Kind::Text => Ok(Value::from(text)),
+        Kind::Time => {
+            let (h, m) = text.split_once(':').ok_or("expected HH:MM")?;
+            match (h.parse::<i64>(), m.parse::<i64>()) {
+                (Ok(h), Ok(m)) if (0..24).contains(&h) && (0..60).contains(&m) => {
+                    Ok(Value::from(h * 60 + m))
+                }
+                _ => Err("expected HH:MM".to_string()),
+            }
+        }
+        Kind::Days => parse_days(text).map(Value::from),
+        Kind::Bool | Kind::Encryption => Err("not editable as text".to_string()),
+    }
+}
+
+/// Parses `every day`, `weekdays`, `weekends` or a list like `Mon,Wed,Fri`.
+fn parse_days(text: &str) -> Result<i64, String> {
+    match text.to_lowercase().as_str() {
+        "every day" | "all" => return Ok(127),
+        "weekdays" => return Ok(62),
+        "weekends" => return Ok(65),
+        _ => {}
+    }
+    let mut mask = 0;
+    for day in text.split([',', ' ']).filter(|d| !d.is_empty()) {
+        let i = DAYS
+            .iter()
+            .position(|d| d.eq_ignore_ascii_case(day))
+            .ok_or_else(|| format!("unknown day {day}, use Sun, Mon, ... or weekdays"))?;
+        mask |= 1 << i;
+    }
+    if mask == 0 {
+        return Err("pick at least one day".to_string());
+    }
+    Ok(mask)
+}
+
+fn session_set_args(changes: &Map<String, Value>) -> SessionSetArgs {
+    let mut args = SessionSetArgs::default();
+    for (key, value) in changes {
+        let b = value.as_bool();
+        let n = value.as_i64().map(|n| n as i32);
+        let s = value.as_str().map(str::to_string);
+        match key.as_str() {
+            "speed-limit-down-enabled" => args.speed_limit_down_enabled = b,
+            "speed-limit-down" => args.speed_limit_down = n,
+            "speed-limit-up-enabled" => args.speed_limit_up_enabled = b,
+            "speed-limit-up" => args.speed_limit_up = n,
+            "alt-speed-enabled" => args.alt_speed_enabled = b,
+            "alt-speed-down" => args.alt_speed_down = n,
+            "alt-speed-up" => args.alt_speed_up = n,
+            "alt-speed-time-enabled" => args.alt_speed_time_enabled = b,
+            "alt-speed-time-begin" => args.alt_speed_time_begin = n,
+            "alt-speed-time-end" => args.alt_speed_time_end = n,
+            "alt-speed-time-day" => args.alt_speed_time_day = n,
+            "download-dir" => args.download_dir = s,
+            "incomplete-dir-enabled" => args.incomplete_dir_enabled = b,
+            "incomplete-dir" => args.incomplete_dir = s,
+            "start-added-torrents" => args.start_added_torrents = b,
+            "peer-port" => args.peer_port = n,
+            "encryption" => args.encryption = s,
+            "dht-enabled" => args.dht_enabled = b,
+            "pex-enabled" => args.pex_enabled = b,
+            "lpd-enabled" => args.lpd_enabled = b,
+            "utp-enabled" => args.utp_enabled = b,
+            "download-queue-enabled" => args.download_queue_enabled = b,
+            "download-queue-size" => args.download_queue_size = n,
+            "seed-queue-enabled" => args.seed_queue_enabled = b,
+            "seed-queue-size" => args.seed_queue_size = n,
+            "queue-stalled-enabled" => args.queue_stalled_enabled = b,
+            "queue-stalled-minutes" => args.queue_stalled_minutes = n,
+            "seedRatioLimited" => args.seed_ratio_limited = b,
+            "seedRatioLimit" => args.seed_ratio_limit = value.as_f64().map(|r| r as f32),
+            "idle-seeding-limit-enabled" => args.idle_seeding_limit_enabled = b,
+            "idle-seeding-limit" => args.idle_seeding_limit = n,
+            _ => {}
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_groups() {
+        assert_eq!(parse_days("every day"), Ok(127));
+        assert_eq!(parse_days("All"), Ok(127));
+        assert_eq!(parse_days("weekdays"), Ok(62));
+        assert_eq!(parse_days("Weekends"), Ok(65));
+    }
+
+    #[test]
+    fn day_lists() {
+        assert_eq!(parse_days("Sun"), Ok(1));
+        assert_eq!(parse_days("Mon,Wed,Fri"), Ok(2 | 8 | 32));
+        assert_eq!(parse_days("sat sun"), Ok(64 | 1));
+        assert_eq!(parse_days("Mon, Mon"), Ok(2));
+    }
+
+    #[test]
+    fn invalid_days() {
+        assert!(parse_days("").is_err());
+        assert!(parse_days(" , ").is_err());
+        assert!(parse_days("Monday").is_err());
+        assert!(parse_days("Mon,Funday").is_err());
+    }
+}
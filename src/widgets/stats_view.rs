@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect, Size},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Row, Table, TableState},
+};
+use transmission_rpc::types::{SessionStats, Torrent};
+use url::Url;
+
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    util::{centered_rect, readable_size, readable_time},
+};
+
+/// Totals of the torrents sharing a tracker or label.
+#[derive(Default)]
+struct Group {
+    torrents: usize,
+    size: u64,
+    downloaded: u64,
+    uploaded: u64,
+}
+
+/// `session-stats` totals next to per-tracker and per-label breakdowns.
+pub struct StatsView {
+    stats: Result<SessionStats, String>,
+    trackers: Vec<(String, Group)>,
+    labels: Vec<(String, Group)>,
+    /// Whether the label table has focus instead of the tracker table.
+    labels_focused: bool,
+    tracker_state: TableState,
+    label_state: TableState,
+}
+
+impl StatsView {
+    pub fn new(stats: Result<SessionStats, String>, torrents: &[Torrent]) -> Self {
+        let mut view = Self {
+            stats,
+            trackers: vec![],
+            labels: vec![],
+            labels_focused: false,
+            tracker_state: TableState::default().with_selected(Some(0)),
+            label_state: TableState::default().with_selected(Some(0)),
+        };
+        view.set_torrents(torrents);
+        view
+    }
+
+    pub fn set_data(&mut self, stats: Result<SessionStats, String>, torrents: &[Torrent]) {
+        self.stats = stats;
+        self.set_torrents(torrents);
+    }
+
+    fn set_torrents(&mut self, torrents: &[Torrent]) {
+        let mut trackers: BTreeMap<String, Group> = BTreeMap::new();
+        let mut labels: BTreeMap<String, Group> = BTreeMap::new();
+        let add = |group: &mut Group, t: &Torrent| {
+            group.torrents += 1;
+            group.size += t.total_size.unwrap_or(0).max(0) as u64;
+            group.downloaded += t.downloaded_ever.unwrap_or(0);
+            group.uploaded += t.uploaded_ever.unwrap_or(0).max(0) as u64;
+        };
+
+        for t in torrents {
+            // Grouped by the primary tracker so every torrent is counted once.
+            let tracker = t
+                .trackers
+                .as_ref()
+                .and_then(|list| list.first())
+                .map(|tracker| tracker_host(&tracker.announce))
+                .unwrap_or_else(|| "(no tracker)".to_string());
+            add(trackers.entry(tracker).or_default(), t);
+
+            match t.labels.as_deref() {
+                Some(list) if !list.is_empty() => {
+                    for label in list {
+                        add(labels.entry(label.clone()).or_default(), t);
+                    }
+                }
+                _ => add(labels.entry("(no label)".to_string()).or_default(), t),
+            }
+        }
+
+        self.trackers = sorted(trackers);
+        self.labels = sorted(labels);
+    }
+
+    /// Returns whether to close and whether the data should be refreshed.
+    pub fn handler(&mut self, key: KeyEvent, keys: &Keymap) -> (bool, bool) {
+        let (state, len) = if self.labels_focused {
+            (&mut self.label_state, self.labels.len())
+        } else {
+            (&mut self.tracker_state, self.trackers.len())
+        };
+        match keys.action(Context::Stats, &key) {
+            Some(Action::Down) if len > 0 => {
+                let next = state.selected().map_or(0, |i| (i + 1) % len);
+                state.select(Some(next));
+            }
+            Some(Action::Up) if len > 0 => {
+                let prev = state.selected().map_or(0, |i| (i + len - 1) % len);
+                state.select(Some(prev));
+            }
+            Some(Action::SwitchTable) => self.labels_focused = !self.labels_focused,
+            Some(Action::Refresh) => return (false, true),
+            Some(Action::Close) => return (true, false),
+            _ => {}
+        }
+        (false, false)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(80, 80, Size::new(60, 18), frame.area());
+        frame.render_widget(Clear, area);
+
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        let block = Block::default()
+            .title(" Statistics ")
+            .title_bottom(format!(
+                " {} ",
+                keys.hints(
+                    Context::Stats,
+                    &[
+                        (Action::SwitchTable, "switch table"),
+                        (Action::Refresh, "refresh"),
+                        (Action::Close, "close"),
+                    ],
+                    " ",
+                )
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .style(fg);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(9), Constraint::Min(0)])
+            .split(inner);
+
+        match &self.stats {
+            Ok(stats) => {
+                let header = Row::new(["", "Current session", "Cumulative"])
+                    .style(fg.add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+                // transmission-rpc does not export the `Stats` type of both columns.
+                macro_rules! column {
+                    ($s:expr) => {
+                        [
+                            readable_size($s.downloaded_bytes.max(0) as u64),
+                            readable_size($s.uploaded_bytes.max(0) as u64),
+                            ratio($s.uploaded_bytes, $s.downloaded_bytes),
+                            $s.files_added.to_string(),
+                            $s.session_count.map_or("-".to_string(), |n| n.to_string()),
+                            readable_time($s.seconds_active),
+                        ]
+                    };
+                }
+                let labels = [
+                    "Downloaded",
+                    "Uploaded",
+                    "Ratio",
+                    "Files added",
+                    "Sessions",
+                    "Time active",
+                ];
+                let rows: Vec<Row> = labels
+                    .into_iter()
+                    .zip(column!(stats.current_stats))
+                    .zip(column!(stats.cumulative_stats))
+                    .map(|((label, current), total)| Row::new([label.to_string(), current, total]))
+                    .collect();
+                let table = Table::new(
+                    rows,
+                    [
+                        Constraint::Length(14),
+                        Constraint::Length(20),
+                        Constraint::Length(20),
+                    ],
+                )
+                .header(header)
+                .style(fg)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(muted),
+                );
+                frame.render_widget(table, chunks[0]);
+            }
+            Err(e) => frame.render_widget(
+                Paragraph::new(format!("Failed to load session stats: {e}")).style(fg),
+                chunks[0],
+            ),
+        }
+
+        let tables = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+        render_groups(
+            frame,
+            tables[0],
+            theme,
+            " By tracker ",
+            &self.trackers,
+            &mut self.tracker_state,
+            !self.labels_focused,
+        );
+        render_groups(
+            frame,
+            tables[1],
+            theme,
+            " By label ",
+            &self.labels,
+            &mut self.label_state,
+            self.labels_focused,
+        );
+    }
+}
+
+fn render_groups(
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    title: &str,
+    groups: &[(String, Group)],
+    state: &mut TableState,
+    focused: bool,
+) {
+    let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+    let border = if focused {
+        fg
+    } else {
+        Style::default().fg(Theme::color(&theme.details.muted_fg))
+    };
+
+    let header = Row::new(["Name", "Torrents", "Size", "Down", "Up", "Ratio"])
+        .style(fg.add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+    let rows: Vec<Row> = groups
+        .iter()
+        .map(|(name, g)| {
+            Row::new([
+                name.clone(),
+                g.torrents.to_string(),
+                readable_size(g.size),
+                readable_size(g.downloaded),
+                readable_size(g.uploaded),
+                ratio(g.uploaded as i64, g.downloaded as i64),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header)
+    .style(fg)
+    .block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(border)
+            .padding(Padding::horizontal(1)),
+    )
+    .row_highlight_style(if focused {
+        Style::default()
+            .fg(Theme::color(&theme.table.row_highlight_fg))
+            .bg(Theme::color(&theme.table.row_highlight_bg))
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    });
+    frame.render_stateful_widget(table, area, state);
+}
+
+/// Groups with the most uploaded data first.
+fn sorted(groups: BTreeMap<String, Group>) -> Vec<(String, Group)> {
+    let mut groups: Vec<(String, Group)> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, g)| std::cmp::Reverse(g.uploaded));
+    groups
+}
+
+fn tracker_host(announce: &str) -> String {
+    Url::parse(announce)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| announce.to_string())
+}
+
+fn ratio(uploaded: i64, downloaded: i64) -> String {
+    if downloaded <= 0 {
+        return "-".to_string();
+    }
+    format!("{:.2}", uploaded as f64 / downloaded as f64)
+}
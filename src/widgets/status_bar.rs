@@ -0,0 +1,123 @@
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use transmission_rpc::types::{Torrent, TorrentStatus};
+
+use crate::config::Theme;
+use crate::keymap::{Action, Context, Keymap};
+use crate::session::SessionStatus;
+use crate::util::{readable_size, readble_speed};
+
+/// One line under the layout with totals over all torrents and the daemon state.
+#[derive(Default)]
+pub struct StatusBar {
+    download_speed: i64,
+    upload_speed: i64,
+    total: usize,
+    downloading: usize,
+    seeding: usize,
+    stopped: usize,
+    checking: usize,
+    session: SessionStatus,
+    /// Active sort and filter of the torrent list.
+    view: String,
+}
+
+impl StatusBar {
+    pub fn set_data(&mut self, torrents: &[Torrent], session: SessionStatus) {
+        self.download_speed = torrents.iter().filter_map(|t| t.rate_download).sum();
+        self.upload_speed = torrents.iter().filter_map(|t| t.rate_upload).sum();
+        self.total = torrents.len();
+        let count = |states: &[TorrentStatus]| {
+            torrents
+                .iter()
+                .filter(|t| t.status.is_some_and(|s| states.contains(&s)))
+                .count()
+        };
+        self.downloading = count(&[TorrentStatus::Downloading, TorrentStatus::QueuedToDownload]);
+        self.seeding = count(&[TorrentStatus::Seeding, TorrentStatus::QueuedToSeed]);
+        self.stopped = count(&[TorrentStatus::Stopped]);
+        self.checking = count(&[TorrentStatus::Verifying, TorrentStatus::QueuedToVerify]);
+        self.session = session;
+    }
+
+    pub fn set_view(&mut self, view: String) {
+        self.view = view;
+    }
+
+    pub fn alt_speed_enabled(&self) -> Option<bool> {
+        self.session.alt_speed_enabled
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+        let accent = Style::default().fg(Theme::color(&theme.details.accent_fg));
+        let sep = Span::styled("  │  ", muted);
+
+        let mut left = vec![
+            Span::styled("↓ ", accent),
+            Span::styled(readble_speed(self.download_speed), fg),
+            Span::raw("  "),
+            Span::styled("↑ ", accent),
+            Span::styled(readble_speed(self.upload_speed), fg),
+            sep.clone(),
+            Span::styled(format!("{} torrents", self.total), fg),
+            Span::styled(
+                format!(
+                    "  {} downloading · {} seeding · {} stopped",
+                    self.downloading, self.seeding, self.stopped
+                ),
+                muted,
+            ),
+        ];
+        if self.checking > 0 {
+            left.push(Span::styled(
+                format!(" · {} checking", self.checking),
+                muted,
+            ));
+        }
+        if let Some(free) = self.session.free_space {
+            left.push(sep);
+            left.push(Span::styled(
+                format!("{} free", readable_size(free.max(0) as u64)),
+                fg,
+            ));
+        }
+
+        if !self.view.is_empty() {
+            left.push(Span::styled("  │  ", muted));
+            left.push(Span::styled(self.view.clone(), accent));
+        }
+
+        let key = keys
+            .key(Context::Global, Action::ToggleTurtle)
+            .map(|key| format!(" [{key}]"))
+            .unwrap_or_default();
+        let turtle = match self.session.alt_speed_enabled {
+            Some(true) => Span::styled(
+                format!("Turtle on{key} "),
+                Style::default()
+                    .fg(Theme::color(&theme.details.success_fg))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Some(false) => Span::styled(format!("Turtle off{key} "), muted),
+            None => Span::raw(""),
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(turtle.width() as u16 + 1),
+            ])
+            .split(area);
+        frame.render_widget(Paragraph::new(Line::from(left)), chunks[0]);
+        frame.render_widget(
+            Paragraph::new(Line::from(turtle)).alignment(Alignment::Right),
+            chunks[1],
+        );
+    }
+}
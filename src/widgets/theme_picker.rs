@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Size},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Row, Table, TableState},
+};
+
+use crate::{
+    config::{Theme, resolve_theme, theme_names},
+    keymap::{Action, Context, Keymap},
+    util::centered_rect,
+};
+
+/// Bundled and user themes. The app applies the highlighted theme as the
+/// selection moves and puts `original` back on cancel, themes that fail to
+/// load are reported on the status row.
+pub struct ThemePicker {
+    names: Vec<String>,
+    state: TableState,
+    /// The theme in use when the picker was opened.
+    pub original: Theme,
+    /// Themes read so far, each file is loaded once per picker.
+    loaded: HashMap<String, Result<Theme, String>>,
+}
+
+impl ThemePicker {
+    pub fn new(current: &str, original: Theme) -> Self {
+        let names = theme_names();
+        let index = names.iter().position(|n| n == current).unwrap_or(0);
+        Self {
+            names,
+            state: TableState::default().with_selected(Some(index)),
+            original,
+            loaded: HashMap::new(),
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        let next = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + 1) % self.names.len());
+        self.state.select(Some(next));
+    }
+
+    pub fn select_prev(&mut self) {
+        let prev = match self.state.selected() {
+            Some(0) | None => self.names.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(prev));
+    }
+
+    pub fn selected(&self) -> &str {
+        &self.names[self.state.selected().unwrap_or(0)]
+    }
+
+    /// The highlighted theme, or why it could not be loaded.
+    pub fn theme(&mut self) -> &Result<Theme, String> {
+        let name = self.names[self.state.selected().unwrap_or(0)].clone();
+        self.loaded
+            .entry(name)
+            .or_insert_with_key(|name| resolve_theme(name))
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        let longest = self.names.iter().map(|n| n.len()).max().unwrap_or(0) as u16;
+        let min = Size::new((longest + 6).max(30), self.names.len() as u16 + 4);
+        let area = centered_rect(25, 40, min, frame.area());
+        frame.render_widget(Clear, area);
+
+        let hints = keys.hints(
+            Context::Menu,
+            &[(Action::Confirm, "keep"), (Action::Close, "undo")],
+            " ",
+        );
+        let block = Block::default()
+            .title(" Theme ")
+            .title_bottom(Line::from(Span::styled(format!(" {hints} "), muted)))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(fg)
+            .padding(Padding::uniform(1))
+            .style(fg);
+
+        let rows: Vec<Row> = self.names.iter().map(|n| Row::new([n.as_str()])).collect();
+        let table = Table::new(rows, [Constraint::Percentage(100)])
+            .row_highlight_style(
+                Style::default()
+                    .fg(Theme::color(&theme.table.row_highlight_fg))
+                    .bg(Theme::color(&theme.table.row_highlight_bg))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .block(block);
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::config::Theme;
+use crate::util::row_at;
 use ratatui::Frame;
 use ratatui::layout::Constraint;
+use ratatui::layout::Position;
 use ratatui::layout::Rect;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
@@ -19,6 +21,11 @@ pub struct TorrentActions {
     pub id: Id,
     pub name: String,
     state: TableState,
+    /// Set when opened with a right-click, the menu is then drawn next to
+    /// the cursor instead of in the middle of the screen.
+    pub anchor: Option<Position>,
+    /// Where the menu was last drawn, for mouse clicks.
+    area: Rect,
 }
 
 impl TorrentActions {
@@ -27,6 +34,8 @@ impl TorrentActions {
             id,
             name,
             state: TableState::default(),
+            anchor: None,
+            area: Rect::default(),
         }
     }
 
@@ -44,6 +53,19 @@ impl TorrentActions {
         }
     }
 
+    /// Selects the entry under a mouse position, returns whether there was one.
+    pub fn select_at(&mut self, column: u16, row: u16) -> bool {
+        let hit = row_at(self.area, 2, 0, column, row).filter(|i| *i < ACTIONS_STR.len());
+        if hit.is_some() {
+            self.state.select(hit);
+        }
+        hit.is_some()
+    }
+
+    pub fn contains(&self, column: u16, row: u16) -> bool {
+        self.area.contains(Position::new(column, row))
+    }
+
     pub fn get_selected(&self) -> Option<&str> {
         let index = self.state.selected();
         match index {
@@ -53,12 +75,27 @@ impl TorrentActions {
     }
 
     pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
-        let area = Rect {
-            x: frame.area().width / 4,
-            y: frame.area().height / 3,
-            width: frame.area().width / 2,
-            height: frame.area().height / 2,
+        let screen = frame.area();
+        let area = match self.anchor {
+            Some(anchor) => {
+                let longest = ACTIONS_STR.iter().map(|a| a.len()).max().unwrap_or(0);
+                let width = (self.name.chars().count().max(longest) as u16 + 4).min(screen.width);
+                let height = (ACTIONS_STR.len() as u16 + 4).min(screen.height);
+                Rect {
+                    x: anchor.x.min(screen.width - width),
+                    y: anchor.y.min(screen.height - height),
+                    width,
+                    height,
+                }
+            }
+            None => Rect {
+                x: screen.width / 4,
+                y: screen.height / 3,
+                width: screen.width / 2,
+                height: screen.height / 2,
+            },
         };
+        self.area = area;
         Clear.render(area, frame.buffer_mut());
         let rows: Vec<Row> = ACTIONS_STR
             .iter()
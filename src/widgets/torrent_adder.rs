@@ -1,12 +1,19 @@
 use crate::{
     config::Theme,
-    util::{centered_rect, readable_size},
+    file_tree::FileTree,
+    keymap::{Action, Context, Keymap},
+    util::{centered_rect, path_pattern, readable_size},
+    widgets::{
+        add_options::AddOptions,
+        input::{Input, InputMode},
+    },
 };
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use crossterm::event::KeyEvent;
 use lava_torrent::torrent::v1::Torrent;
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Size},
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
@@ -16,9 +23,22 @@ use ratatui::{
 };
 use transmission_rpc::types::{Priority, TorrentAddArgs};
 
+/// Add arguments pointing at a local `.torrent`, either by path or by uploading
+/// its contents as `metainfo`.
+pub fn torrent_source(path: &str, upload: bool) -> Result<TorrentAddArgs, String> {
+    let mut args = TorrentAddArgs::default();
+    if upload {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+        args.metainfo = Some(STANDARD.encode(bytes));
+    } else {
+        args.filename = Some(path.to_string());
+    }
+    Ok(args)
+}
+
 #[derive(Clone, Debug)]
 pub struct FileEntry {
-    name: String,
+    path: String,
     size: u64,
     selected: bool,
     priority: Priority,
@@ -27,52 +47,138 @@ pub struct FileEntry {
 pub struct TorrentAdder {
     path: String,
     entries: Vec<FileEntry>,
+    tree: FileTree,
     state: TableState,
     scroll_state: ScrollbarState,
     torrent_name: String,
     total_size: u64,
+    pub info_hash: Option<String>,
+    pub trackers: Vec<String>,
+    /// Pattern input, `Some(true)` selects matching files and `Some(false)` deselects them.
+    pattern: Option<bool>,
+    input: Input,
+    message: Option<String>,
+    pub options: AddOptions,
+    low_space_confirmed: bool,
+    /// Send the file contents as `metainfo` so remote daemons can add it.
+    upload: bool,
 }
 
 impl TorrentAdder {
-    pub fn new(path: String) -> Self {
+    pub fn new(path: String, options: AddOptions, upload: bool) -> Self {
         let mut adder = Self {
             path: path.clone(),
             entries: Vec::new(),
+            tree: FileTree::new::<&str>(&[]),
             state: TableState::default(),
             scroll_state: ScrollbarState::default(),
             torrent_name: String::new(),
             total_size: 0,
+            info_hash: None,
+            trackers: vec![],
+            pattern: None,
+            input: Input::new(),
+            message: None,
+            options,
+            low_space_confirmed: false,
+            upload,
         };
         adder.load_torrent();
         adder
     }
 
-    pub fn handler(&mut self, key: KeyEvent) -> (bool, Option<TorrentAddArgs>) {
-        match key.code {
-            KeyCode::Down | KeyCode::Char('j') => {
+    /// Context of the keys read right now, `None` while typing a pattern.
+    pub fn key_context(&self) -> Option<Context> {
+        if self.options.is_focused {
+            self.options.key_context()
+        } else if self.pattern.is_some() {
+            None
+        } else {
+            Some(Context::Adder)
+        }
+    }
+
+    pub async fn handler(
+        &mut self,
+        key: KeyEvent,
+        keys: &Keymap,
+    ) -> (bool, Option<TorrentAddArgs>) {
+        if self.options.is_focused {
+            self.options.handler(key, keys).await;
+            self.low_space_confirmed = false;
+            return (false, None);
+        }
+
+        if let Some(select) = self.pattern {
+            if let Some(pattern) = self.input.handler(key) {
+                self.select_matching(&pattern, select);
+            }
+            if !self.input.is_active {
+                self.pattern = None;
+            }
+            return (false, None);
+        }
+
+        match keys.action(Context::Adder, &key) {
+            Some(Action::Down) => {
                 self.next();
                 (false, None)
             }
-            KeyCode::Up | KeyCode::Char('k') => {
+            Some(Action::Up) => {
                 self.prev();
                 (false, None)
             }
-            KeyCode::Char(' ') => {
+            Some(Action::Fold) => {
+                if let Some(node) = self.selected_node() {
+                    self.tree.toggle_collapsed(node);
+                }
+                (false, None)
+            }
+            Some(Action::ToggleWanted) => {
                 self.toggle_selected();
                 (false, None)
             }
-            KeyCode::Char('p') => {
+            Some(Action::CyclePriority) => {
                 self.cycle_priority();
                 (false, None)
             }
-            KeyCode::Char('a') => {
+            Some(Action::ToggleAll) => {
                 self.toggle_all();
                 (false, None)
             }
-            KeyCode::Enter => {
-                let mut t = TorrentAddArgs {
-                    filename: Some(self.path.to_string()),
-                    ..Default::default()
+            Some(Action::Options) => {
+                self.options.is_focused = true;
+                (false, None)
+            }
+            Some(action @ (Action::SelectPattern | Action::DeselectPattern)) => {
+                self.pattern = Some(action == Action::SelectPattern);
+                self.message = None;
+                self.input.is_active = true;
+                self.input.input_mode = InputMode::Editing;
+                self.input.reset_cursor();
+                (false, None)
+            }
+            Some(Action::Confirm) => {
+                let selected_size = self.selected_size();
+                if let Some(free) = self.options.free_space()
+                    && (free.max(0) as u64) < selected_size
+                    && !self.low_space_confirmed
+                {
+                    self.low_space_confirmed = true;
+                    self.message = Some(format!(
+                        "Only {} free in {}, press Enter again to add anyway",
+                        readable_size(free.max(0) as u64),
+                        self.options.download_dir,
+                    ));
+                    return (false, None);
+                }
+
+                let mut t = match torrent_source(&self.path, self.upload) {
+                    Ok(t) => t,
+                    Err(e) => {
+                        self.message = Some(e);
+                        return (false, None);
+                    }
                 };
 
                 let unwanted: Vec<i32> = self
@@ -111,10 +217,12 @@ impl TorrentAdder {
                     .collect();
                 t.priority_high = Some(p_high);
 
+                self.options.apply(&mut t);
+                self.options.remember_download_dir();
+
                 (true, Some(t))
             }
-            KeyCode::Char('q') => (true, None),
-            KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => (true, None),
+            Some(Action::Close) => (true, None),
             _ => (false, None),
         }
     }
@@ -126,18 +234,24 @@ impl TorrentAdder {
         };
 
         self.torrent_name = torrent.name.clone();
+        self.info_hash = Some(torrent.info_hash());
+        if let Some(tiers) = &torrent.announce_list {
+            self.trackers = tiers.iter().flatten().cloned().collect();
+        } else if let Some(announce) = &torrent.announce {
+            self.trackers.push(announce.clone());
+        }
 
         if let Some(files) = &torrent.files {
             for file in files {
-                let name = file
+                let path = file
                     .path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
                 self.total_size += file.length as u64;
                 self.entries.push(FileEntry {
-                    name,
+                    path,
                     size: file.length as u64,
                     selected: true,
                     priority: Priority::Normal,
@@ -146,49 +260,72 @@ impl TorrentAdder {
         } else {
             self.total_size = torrent.length as u64;
             self.entries.push(FileEntry {
-                name: torrent.name.clone(),
+                path: torrent.name.clone(),
                 size: torrent.length as u64,
                 selected: true,
                 priority: Priority::Normal,
             });
         }
 
+        let paths: Vec<&str> = self.entries.iter().map(|e| e.path.as_str()).collect();
+        self.tree = FileTree::new(&paths);
+
         if !self.entries.is_empty() {
             self.state.select(Some(0));
-            self.scroll_state = ScrollbarState::new(self.entries.len().saturating_sub(1));
+            self.scroll_state = ScrollbarState::new(self.tree.nodes.len().saturating_sub(1));
         }
     }
 
+    fn selected_node(&self) -> Option<usize> {
+        let visible = self.tree.visible();
+        self.state.selected().and_then(|i| visible.get(i).copied())
+    }
+
     pub fn next(&mut self) {
+        let len = self.tree.visible().len();
+        if len == 0 {
+            return;
+        }
         match self.state.selected() {
-            Some(n) if n >= self.entries.len() - 1 => self.state.select(Some(0)),
+            Some(n) if n >= len - 1 => self.state.select(Some(0)),
             _ => self.state.select_next(),
         }
     }
 
     pub fn prev(&mut self) {
+        let len = self.tree.visible().len();
+        if len == 0 {
+            return;
+        }
         match self.state.selected() {
-            Some(0) => self.state.select(Some(self.entries.len() - 1)),
+            Some(0) => self.state.select(Some(len - 1)),
             _ => self.state.select_previous(),
         }
     }
 
+    /// Toggles the focused file, or every file below the focused folder.
     pub fn toggle_selected(&mut self) {
-        if let Some(i) = self.state.selected()
-            && let Some(entry) = self.entries.get_mut(i)
-        {
-            entry.selected = !entry.selected;
+        if let Some(node) = self.selected_node() {
+            let files = &self.tree.nodes[node].files;
+            let all_selected = files.iter().all(|i| self.entries[*i].selected);
+            for i in files {
+                self.entries[*i].selected = !all_selected;
+            }
         }
     }
 
+    /// Cycles the priority of the focused file or folder. Folders with mixed
+    /// priorities are reset to normal.
     pub fn cycle_priority(&mut self) {
-        if let Some(i) = self.state.selected()
-            && let Some(entry) = self.entries.get_mut(i)
-        {
-            entry.priority = match entry.priority {
-                Priority::Low => Priority::Normal,
-                Priority::Normal => Priority::High,
-                Priority::High => Priority::Low,
+        if let Some(node) = self.selected_node() {
+            let files = &self.tree.nodes[node].files;
+            let next = match self.common_priority(files) {
+                Some(Priority::Low) | None => Priority::Normal,
+                Some(Priority::Normal) => Priority::High,
+                Some(Priority::High) => Priority::Low,
+            };
+            for i in files {
+                self.entries[*i].priority = next;
             }
         }
     }
@@ -200,8 +337,44 @@ impl TorrentAdder {
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, theme: &Theme) {
-        let area = centered_rect(80, 66, frame.area());
+    fn select_matching(&mut self, pattern: &str, select: bool) {
+        let re = match path_pattern(pattern) {
+            Ok(re) => re,
+            Err(e) => {
+                self.message = Some(format!("Invalid pattern: {e}"));
+                return;
+            }
+        };
+
+        let mut count = 0;
+        for entry in self.entries.iter_mut().filter(|e| re.is_match(&e.path)) {
+            entry.selected = select;
+            count += 1;
+        }
+        self.message = Some(format!(
+            "{} {count} file(s) matching {pattern}",
+            if select { "Selected" } else { "Deselected" }
+        ));
+    }
+
+    fn selected_size(&self) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.selected)
+            .map(|e| e.size)
+            .sum()
+    }
+
+    fn common_priority(&self, files: &[usize]) -> Option<Priority> {
+        let first = self.entries.get(*files.first()?)?.priority;
+        files
+            .iter()
+            .all(|i| self.entries[*i].priority == first)
+            .then_some(first)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(80, 66, Size::new(70, 20), frame.area());
         frame.render_widget(Clear, area);
 
         // ── outer layout: title block + table + footer ──────────────────────
@@ -210,17 +383,13 @@ impl TorrentAdder {
             .constraints([
                 Constraint::Length(3), // header / torrent name
                 Constraint::Min(0),    // file table
-                Constraint::Length(3), // footer: totals + keybinds
+                Constraint::Length(7), // add options
+                Constraint::Length(if self.pattern.is_some() { 4 } else { 3 }), // footer
             ])
             .split(area);
 
         // ── header ──────────────────────────────────────────────────────────
-        let selected_size: u64 = self
-            .entries
-            .iter()
-            .filter(|e| e.selected)
-            .map(|e| e.size)
-            .sum();
+        let selected_size = self.selected_size();
 
         let header_block = Block::default()
             .title(format!(
@@ -239,20 +408,31 @@ impl TorrentAdder {
         let selected_idx = self.state.selected().unwrap_or(0);
 
         let rows: Vec<Row> = self
-            .entries
-            .iter()
+            .tree
+            .visible()
+            .into_iter()
             .enumerate()
-            .map(|(i, entry)| {
+            .map(|(i, node_index)| {
+                let node = &self.tree.nodes[node_index];
                 let is_focused = i == selected_idx;
 
-                let checkbox = if entry.selected { "  " } else { "  " };
+                let selected_count = node
+                    .files
+                    .iter()
+                    .filter(|f| self.entries[**f].selected)
+                    .count();
+                let checkbox = match selected_count {
+                    0 => "  ",
+                    n if n == node.files.len() => "  ",
+                    _ => "  ",
+                };
 
                 let row_style = if is_focused {
                     Style::default()
                         .fg(Theme::color(&theme.table.row_highlight_fg))
                         .bg(Theme::color(&theme.table.row_highlight_bg))
                         .add_modifier(Modifier::BOLD)
-                } else if !entry.selected {
+                } else if selected_count == 0 {
                     Style::default()
                         .fg(Theme::color(&theme.general.foreground))
                         .add_modifier(Modifier::DIM)
@@ -260,14 +440,28 @@ impl TorrentAdder {
                     Style::default().fg(Theme::color(&theme.general.foreground))
                 };
 
+                let indent = "  ".repeat(node.depth);
+                let name = if node.is_dir {
+                    let arrow = if self.tree.is_collapsed(node_index) {
+                        "▸"
+                    } else {
+                        "▾"
+                    };
+                    format!("{indent}{arrow} {}/", node.name)
+                } else {
+                    format!("{indent}{}", node.name)
+                };
+                let size: u64 = node.files.iter().map(|f| self.entries[*f].size).sum();
+
                 Row::new([
                     Cell::from(checkbox),
-                    Cell::from(entry.name.clone()),
-                    Cell::from(readable_size(entry.size)),
-                    Cell::from(match entry.priority {
-                        Priority::Low => "Low",
-                        Priority::Normal => "Normal",
-                        Priority::High => "High",
+                    Cell::from(name),
+                    Cell::from(readable_size(size)),
+                    Cell::from(match self.common_priority(&node.files) {
+                        Some(Priority::Low) => "Low",
+                        Some(Priority::Normal) => "Normal",
+                        Some(Priority::High) => "High",
+                        None => "Mixed",
                     })
                     .style(row_style),
                 ])
@@ -321,24 +515,45 @@ impl TorrentAdder {
             &mut self.scroll_state,
         );
 
+        // ── pattern input ────────────────────────────────────────────────────
+        if self.pattern.is_some() {
+            self.input.render(frame, chunks[3], theme);
+            self.options.render(frame, chunks[2], theme, keys);
+            return;
+        }
+
         // ── footer keybinds ──────────────────────────────────────────────────
         let footer_block = Block::default()
             .borders(Borders::ALL)
             .style(Style::default().fg(Theme::color(&theme.general.foreground)));
 
-        let hints = Line::from(vec![
-            Span::raw(" [↑↓ navigate]  "),
-            Span::raw("[Space select]  "),
-            Span::raw("[p priority]  "),
-            Span::raw("[a select all]  "),
-            Span::raw("[Enter confirm]  "),
-            Span::raw("[Esc cancel] "),
-        ]);
+        let hints = match &self.message {
+            Some(message) => Line::from(Span::raw(message.clone())),
+            None => Line::from(keys.hints(
+                Context::Adder,
+                &[
+                    (Action::Down, "down"),
+                    (Action::Up, "up"),
+                    (Action::Fold, "fold"),
+                    (Action::ToggleWanted, "select"),
+                    (Action::CyclePriority, "priority"),
+                    (Action::ToggleAll, "select all"),
+                    (Action::SelectPattern, "select pattern"),
+                    (Action::DeselectPattern, "deselect pattern"),
+                    (Action::Options, "options"),
+                    (Action::Confirm, "confirm"),
+                    (Action::Close, "cancel"),
+                    (Action::Help, "help"),
+                ],
+                "  ",
+            )),
+        };
 
         let footer = ratatui::widgets::Paragraph::new(hints)
             .block(footer_block)
             .alignment(Alignment::Center);
 
-        frame.render_widget(footer, chunks[2]);
+        frame.render_widget(footer, chunks[3]);
+        self.options.render(frame, chunks[2], theme, keys);
     }
 }
@@ -0,0 +1,567 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossterm::event::KeyEvent;
+use lava_torrent::bencode::BencodeElem;
+use lava_torrent::torrent::v1::{TorrentBuild, TorrentBuilder};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Size},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Gauge, Padding, Paragraph, Row, Table, TableState, Wrap},
+};
+use transmission_rpc::types::TorrentAddArgs;
+
+use crate::{
+    config::Theme,
+    keymap::{Action, Context, Keymap},
+    util::{centered_rect, expand_path, readable_size},
+    widgets::{
+        file_picker::FilePicker,
+        input::{Input, InputMode},
+        torrent_adder::torrent_source,
+    },
+};
+
+const MIN_PIECE_LENGTH: i64 = 16 * 1024;
+const MAX_PIECE_LENGTH: i64 = 16 * 1024 * 1024;
+/// Auto piece size aims for roughly this many pieces.
+const TARGET_PIECES: i64 = 1500;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Field {
+    Data,
+    Trackers,
+    PieceSize,
+    Private,
+    Comment,
+    Source,
+    Output,
+    Seed,
+}
+
+const FIELDS: [Field; 8] = [
+    Field::Data,
+    Field::Trackers,
+    Field::PieceSize,
+    Field::Private,
+    Field::Comment,
+    Field::Source,
+    Field::Output,
+    Field::Seed,
+];
+
+/// Builds a `.torrent` from local data and optionally adds it for seeding.
+pub struct TorrentCreator {
+    data: String,
+    trackers: String,
+    /// Empty for an automatic piece size.
+    piece_size: String,
+    private: bool,
+    comment: String,
+    source: String,
+    output: String,
+    seed: bool,
+    upload: bool,
+    /// The daemon runs on this machine and can find the data to seed it.
+    local: bool,
+    state: TableState,
+    editing: Option<Field>,
+    input: Input,
+    picker: Option<FilePicker>,
+    /// Walks the data for an auto piece size before the build starts.
+    sizing: Option<(JoinHandle<u64>, PathBuf)>,
+    build: Option<TorrentBuild>,
+    /// Output path and piece length of the running build.
+    building: Option<(PathBuf, i64)>,
+    pub info_hash: Option<String>,
+    message: Option<String>,
+}
+
+impl TorrentCreator {
+    pub fn new(upload: bool, local: bool) -> Self {
+        Self {
+            data: String::new(),
+            trackers: String::new(),
+            piece_size: String::new(),
+            private: false,
+            comment: String::new(),
+            source: String::new(),
+            output: String::new(),
+            seed: local,
+            upload,
+            local,
+            state: TableState::default().with_selected(Some(0)),
+            editing: None,
+            input: Input::new(),
+            picker: Some(FilePicker::source_picker("~/".to_string())),
+            sizing: None,
+            build: None,
+            building: None,
+            info_hash: None,
+            message: None,
+        }
+    }
+
+    pub fn tracker_list(&self) -> Vec<String> {
+        self.trackers
+            .split([',', ' '])
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+
+    fn selected_field(&self) -> Field {
+        FIELDS[self.state.selected().unwrap_or(0).min(FIELDS.len() - 1)]
+    }
+
+    /// `.torrent` written next to the data unless another output was chosen.
+    fn output_path(&self) -> Option<PathBuf> {
+        if !self.output.trim().is_empty() {
+            return Some(expand_path(self.output.trim()));
+        }
+        let data = expand_path(self.data.trim());
+        let name = data.file_name()?.to_string_lossy().to_string();
+        Some(data.with_file_name(format!("{name}.torrent")))
+    }
+
+    /// Context of the keys read right now, `None` while editing a field.
+    pub fn key_context(&self) -> Option<Context> {
+        match &self.picker {
+            Some(picker) => picker.key_context(),
+            None if self.editing.is_some() => None,
+            None => Some(Context::Creator),
+        }
+    }
+
+    pub async fn handler(&mut self, key: KeyEvent, keys: &Keymap) -> bool {
+        if let Some(picker) = self.picker.as_mut() {
+            let (close, paths) = picker.handler(key, keys).await;
+            if let Some(path) = paths.into_iter().next() {
+                self.data = path;
+                self.message = None;
+            }
+            if close {
+                self.picker = None;
+            }
+            return false;
+        }
+
+        if self.build.is_some() || self.sizing.is_some() {
+            if keys.action(Context::Creator, &key) == Some(Action::Close) {
+                if let Some(build) = self.build.take() {
+                    build.cancel();
+                }
+                // A running walk only reads the file system, its result is dropped.
+                self.sizing = None;
+                self.building = None;
+                self.message = Some("Cancelled".to_string());
+            }
+            return false;
+        }
+
+        if let Some(field) = self.editing {
+            if let Some(value) = self.input.handler(key) {
+                self.set_text(field, value);
+            }
+            if !self.input.is_active {
+                self.editing = None;
+            }
+            return false;
+        }
+
+        match keys.action(Context::Creator, &key) {
+            Some(Action::Down) => {
+                let next = (self.state.selected().unwrap_or(0) + 1) % FIELDS.len();
+                self.state.select(Some(next));
+            }
+            Some(Action::Up) => {
+                let prev = (self.state.selected().unwrap_or(0) + FIELDS.len() - 1) % FIELDS.len();
+                self.state.select(Some(prev));
+            }
+            Some(Action::Edit) => self.activate(),
+            Some(Action::Browse) if self.selected_field() == Field::Data => {
+                let start = match Path::new(&self.data).parent() {
+                    Some(parent) if !self.data.is_empty() => parent.display().to_string(),
+                    _ => "~/".to_string(),
+                };
+                self.picker = Some(FilePicker::source_picker(start));
+            }
+            Some(Action::Create) => self.start(),
+            Some(Action::Close) => return true,
+            _ => {}
+        }
+        false
+    }
+
+    fn activate(&mut self) {
+        self.message = None;
+        let field = self.selected_field();
+        let value = match field {
+            Field::Private => {
+                self.private = !self.private;
+                return;
+            }
+            Field::Seed if !self.local => {
+                self.message = Some("Seeding needs the daemon on this machine".to_string());
+                return;
+            }
+            Field::Seed => {
+                self.seed = !self.seed;
+                return;
+            }
+            Field::Data => self.data.clone(),
+            Field::Trackers => self.trackers.clone(),
+            Field::PieceSize => self.piece_size.clone(),
+            Field::Comment => self.comment.clone(),
+            Field::Source => self.source.clone(),
+            Field::Output => self.output.clone(),
+        };
+        self.editing = Some(field);
+        self.input.is_active = true;
+        self.input.input_mode = InputMode::Editing;
+        self.input.set_value(value);
+    }
+
+    fn set_text(&mut self, field: Field, value: String) {
+        let value = value.trim().to_string();
+        match field {
+            Field::Data => self.data = value,
+            Field::Trackers => self.trackers = value,
+            Field::PieceSize => {
+                if value.is_empty() || value.eq_ignore_ascii_case("auto") {
+                    self.piece_size = String::new();
+                } else if parse_piece_size(&value).is_some() {
+                    self.piece_size = value;
+                } else {
+                    self.message = Some(format!(
+                        "Invalid piece size {value}, use a power of two between 16K and 16M"
+                    ));
+                }
+            }
+            Field::Comment => self.comment = value,
+            Field::Source => self.source = value,
+            Field::Output => self.output = value,
+            Field::Private | Field::Seed => {}
+        }
+    }
+
+    fn start(&mut self) {
+        let data = expand_path(self.data.trim());
+        if self.data.trim().is_empty() || !data.exists() {
+            self.message = Some("Choose a file or folder to share first".to_string());
+            return;
+        }
+        let Some(output) = self.output_path() else {
+            self.message = Some("Invalid output path".to_string());
+            return;
+        };
+        match parse_piece_size(&self.piece_size) {
+            Some(piece_length) => self.build(output, piece_length),
+            None => {
+                self.sizing = Some((thread::spawn(move || data_size(&data)), output));
+                self.info_hash = None;
+                self.message = None;
+            }
+        }
+    }
+
+    /// Starts hashing the data in the background.
+    fn build(&mut self, output: PathBuf, piece_length: i64) {
+        let data = expand_path(self.data.trim());
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let mut builder = TorrentBuilder::new(&data, piece_length)
+            .set_privacy(self.private)
+            .add_extra_field(
+                "created by".to_string(),
+                BencodeElem::String(format!("transent {}", env!("CARGO_PKG_VERSION"))),
+            )
+            .add_extra_field("creation date".to_string(), BencodeElem::Integer(now));
+
+        let trackers = self.tracker_list();
+        if let Some(first) = trackers.first() {
+            builder = builder.set_announce(Some(first.clone()));
+        }
+        if trackers.len() > 1 {
+            builder = builder.set_announce_list(trackers.iter().map(|t| vec![t.clone()]).collect());
+        }
+        if !self.comment.is_empty() {
+            builder = builder.add_extra_field(
+                "comment".to_string(),
+                BencodeElem::String(self.comment.clone()),
+            );
+        }
+        if !self.source.is_empty() {
+            builder = builder.add_extra_info_field(
+                "source".to_string(),
+                BencodeElem::String(self.source.clone()),
+            );
+        }
+
+        match builder.build_non_blocking() {
+            Ok(build) => {
+                self.build = Some(build);
+                self.building = Some((output, piece_length));
+                self.info_hash = None;
+                self.message = None;
+            }
+            Err(e) => self.message = Some(format!("Failed to create torrent: {e}")),
+        }
+    }
+
+    /// Writes the torrent once hashing is done. Returns the add arguments when it
+    /// should be seeded.
+    pub fn poll(&mut self) -> Option<TorrentAddArgs> {
+        if self
+            .sizing
+            .as_ref()
+            .is_some_and(|(walk, _)| walk.is_finished())
+            && let Some((walk, output)) = self.sizing.take()
+        {
+            let size = walk.join().unwrap_or(0);
+            self.build(output, auto_piece_length(size));
+        }
+        if !self.build.as_ref()?.is_finished() {
+            return None;
+        }
+        let build = self.build.take()?;
+        let (output, _) = self.building.take()?;
+
+        let torrent = match build.get_output() {
+            Ok(torrent) => torrent,
+            Err(e) => {
+                self.message = Some(format!("Failed to create torrent: {e}"));
+                return None;
+            }
+        };
+        self.info_hash = Some(torrent.info_hash());
+        if let Err(e) = torrent.write_into_file(&output) {
+            self.message = Some(format!("Failed to write {}: {e}", output.display()));
+            return None;
+        }
+        self.message = Some(format!("Written to {}", output.display()));
+        if !self.seed || !self.local {
+            return None;
+        }
+
+        let mut args = match torrent_source(&output.display().to_string(), self.upload) {
+            Ok(args) => args,
+            Err(e) => {
+                self.message = Some(e);
+                return None;
+            }
+        };
+        // The daemon finds the data in its parent dir and verifies it instead of
+        // downloading.
+        args.download_dir = expand_path(self.data.trim())
+            .parent()
+            .map(|p| p.display().to_string());
+        self.message = Some(format!("Written to {}, seeding", output.display()));
+        Some(args)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, theme: &Theme, keys: &Keymap) {
+        let area = centered_rect(60, 60, Size::new(50, FIELDS.len() as u16 + 6), frame.area());
+        frame.render_widget(Clear, area);
+
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+
+        let block = Block::default()
+            .title(" Create torrent ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .padding(Padding::new(1, 1, 1, 0))
+            .style(fg);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(FIELDS.len() as u16),
+                Constraint::Length(1),
+                Constraint::Length(2),
+            ])
+            .split(inner);
+
+        let or = |s: &str, default: &str| {
+            if s.trim().is_empty() {
+                default.to_string()
+            } else {
+                s.to_string()
+            }
+        };
+        let yes_no = |b: bool| if b { "Yes" } else { "No" }.to_string();
+        let output = self
+            .output_path()
+            .filter(|_| !self.data.trim().is_empty())
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+
+        let rows = vec![
+            Row::new(["Data".to_string(), or(&self.data, "(none)")]),
+            Row::new(["Trackers".to_string(), or(&self.trackers, "(none)")]),
+            Row::new(["Piece size".to_string(), or(&self.piece_size, "Auto")]),
+            Row::new(["Private".to_string(), yes_no(self.private)]),
+            Row::new(["Comment".to_string(), self.comment.clone()]),
+            Row::new(["Source".to_string(), self.source.clone()]),
+            Row::new(["Output".to_string(), or(&self.output, &output)]),
+            Row::new([
+                "Seed".to_string(),
+                if self.local {
+                    yes_no(self.seed)
+                } else {
+                    "No (remote daemon)".to_string()
+                },
+            ]),
+        ];
+        let table = Table::new(rows, [Constraint::Length(12), Constraint::Min(10)])
+            .style(fg)
+            .row_highlight_style(
+                Style::default()
+                    .fg(Theme::color(&theme.table.row_highlight_fg))
+                    .bg(Theme::color(&theme.table.row_highlight_bg))
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_stateful_widget(table, chunks[0], &mut self.state);
+
+        if let (Some(build), Some((_, piece_length))) = (&self.build, &self.building) {
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(Theme::color(&theme.progress_bar.filled)))
+                .percent(build.get_progress().min(100) as u16)
+                .label(format!(
+                    "Hashing {}/{} pieces of {}",
+                    build.get_n_piece_processed(),
+                    build.get_n_piece_total(),
+                    readable_size(*piece_length as u64),
+                ));
+            frame.render_widget(gauge, chunks[1]);
+        }
+
+        let cancel = keys
+            .hint(Context::Creator, Action::Close, "cancel")
+            .unwrap_or_default();
+        let footer = match (&self.message, &self.build) {
+            (Some(message), _) => Paragraph::new(message.as_str()).style(fg),
+            (None, Some(_)) => Paragraph::new(cancel).style(muted),
+            (None, None) if self.sizing.is_some() => {
+                Paragraph::new(format!("Measuring data... {cancel}")).style(muted)
+            }
+            (None, None) => {
+                let browse = self.selected_field() == Field::Data;
+                let hints: Vec<(Action, &str)> = [
+                    (Action::Edit, "edit"),
+                    (Action::Browse, "browse"),
+                    (Action::Create, "create"),
+                    (Action::Close, "close"),
+                ]
+                .into_iter()
+                .filter(|(action, _)| browse || *action != Action::Browse)
+                .collect();
+                Paragraph::new(keys.hints(Context::Creator, &hints, " ")).style(muted)
+            }
+        };
+        frame.render_widget(
+            footer
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            chunks[2],
+        );
+
+        if self.editing.is_some() {
+            let popup = centered_rect(50, 20, Input::POPUP_SIZE, frame.area());
+            frame.render_widget(Clear, popup);
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(" Edit field ")
+                .title_alignment(Alignment::Center)
+                .padding(Padding::horizontal(1));
+            let inner = block.inner(popup);
+            frame.render_widget(block, popup);
+            self.input.render(frame, inner, theme);
+        }
+
+        if let Some(picker) = self.picker.as_mut() {
+            picker.render(frame, theme, keys);
+        }
+    }
+}
+
+/// Parses sizes like `512K`, `4M` or `1048576` into a valid piece length.
+fn parse_piece_size(s: &str) -> Option<i64> {
+    let s = s.trim().to_ascii_uppercase();
+    let s = s.trim_end_matches("IB").trim_end_matches('B');
+    let (num, unit) = match s.chars().last()? {
+        'K' => (&s[..s.len() - 1], 1024),
+        'M' => (&s[..s.len() - 1], 1024 * 1024),
+        _ => (s, 1),
+    };
+    let bytes = num.trim().parse::<i64>().ok()? * unit;
+    ((MIN_PIECE_LENGTH..=MAX_PIECE_LENGTH).contains(&bytes) && bytes.count_ones() == 1)
+        .then_some(bytes)
+}
+
+fn auto_piece_length(size: u64) -> i64 {
+    let target = (size as i64 / TARGET_PIECES).max(1);
+    let mut length = MIN_PIECE_LENGTH;
+    while length < target && length < MAX_PIECE_LENGTH {
+        length *= 2;
+    }
+    length
+}
+
+fn data_size(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| data_size(&e.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piece_size_units() {
+        assert_eq!(parse_piece_size("16K"), Some(16 * 1024));
+        assert_eq!(parse_piece_size("512k"), Some(512 * 1024));
+        assert_eq!(parse_piece_size("4M"), Some(4 * 1024 * 1024));
+        assert_eq!(parse_piece_size("4 MiB"), Some(4 * 1024 * 1024));
+        assert_eq!(parse_piece_size("256KB"), Some(256 * 1024));
+        assert_eq!(parse_piece_size("1048576"), Some(1024 * 1024));
+    }
+
+    #[test]
+    fn piece_size_limits() {
+        assert_eq!(parse_piece_size("8K"), None);
+        assert_eq!(parse_piece_size("32M"), None);
+        assert_eq!(parse_piece_size("100K"), None);
+        assert_eq!(parse_piece_size(""), None);
+        assert_eq!(parse_piece_size("big"), None);
+    }
+
+    #[test]
+    fn auto_piece_length_scales_with_size() {
+        assert_eq!(auto_piece_length(0), MIN_PIECE_LENGTH);
+        assert_eq!(auto_piece_length(10 * 1024 * 1024), MIN_PIECE_LENGTH);
+        // 1 GiB over 1500 pieces is about 700 KiB, rounded up to a power of two.
+        assert_eq!(auto_piece_length(1024 * 1024 * 1024), 1024 * 1024);
+        assert_eq!(auto_piece_length(u64::MAX / 2), MAX_PIECE_LENGTH);
+        for size in [1, 5_000_000, 700_000_000, 50_000_000_000] {
+            assert_eq!(auto_piece_length(size).count_ones(), 1);
+        }
+    }
+}
@@ -6,7 +6,17 @@ use ratatui::widgets::{Block, Borders, Cell, Gauge, Padding, Paragraph, Row, Tab
 use transmission_rpc::types::{Torrent, TorrentStatus};
 
 use crate::config::Theme;
-use crate::util::{readabl_eta, readable_size, readable_time, readble_speed, status_to_string};
+use crate::util::{
+    compact_size, compact_speed, readabl_eta, readable_size, readable_time, readble_speed,
+    status_to_string,
+};
+
+/// Rows inside the border the full layout with stat cards needs.
+const FULL_HEIGHT: u16 = 13;
+/// Width inside the border the four stat cards need.
+const CARDS_WIDTH: u16 = 64;
+/// Below this width sizes and speeds use one letter units.
+const SHORT_UNITS_WIDTH: u16 = 60;
 
 pub struct Details {
     pub torrent: Option<Torrent>,
@@ -31,11 +41,34 @@ impl Details {
         let muted_style = Style::default().fg(muted).bg(bg);
         let border_style = Style::default().fg(border_c).bg(bg);
 
+        // Small panes drop the vertical padding and fold the stat cards into
+        // a single line.
+        let compact = area.height < FULL_HEIGHT + 4 || area.width < CARDS_WIDTH + 4;
         let outer_block = Block::default()
             .borders(Borders::ALL)
             .title(Span::styled(" Torrent Details ", border_style))
             .border_style(border_style)
-            .padding(Padding::new(1, 1, 1, 1));
+            .padding(if compact {
+                Padding::horizontal(1)
+            } else {
+                Padding::uniform(1)
+            });
+        let inner = outer_block.inner(area);
+        let short_units = inner.width < SHORT_UNITS_WIDTH;
+        let size = |bytes: u64| {
+            if short_units {
+                compact_size(bytes)
+            } else {
+                readable_size(bytes)
+            }
+        };
+        let speed = |bytes: i64| {
+            if short_units {
+                compact_speed(bytes)
+            } else {
+                readble_speed(bytes)
+            }
+        };
 
         let Some(torrent) = &self.torrent else {
             let p =
@@ -46,18 +79,18 @@ impl Details {
 
         let name = torrent.name.clone().unwrap_or_default();
         let total_size_bytes = torrent.total_size.unwrap_or(0) as u64;
-        let downloaded_bytes = torrent.downloaded_ever.unwrap_or(0) as u64;
-        let total_size = readable_size(total_size_bytes);
-        let downloaded = readable_size(downloaded_bytes);
-        let uploaded = readable_size(torrent.uploaded_ever.unwrap_or(0) as u64);
+        let downloaded_bytes = torrent.downloaded_ever.unwrap_or(0);
+        let total_size = size(total_size_bytes);
+        let downloaded = size(downloaded_bytes);
+        let uploaded = size(torrent.uploaded_ever.unwrap_or(0) as u64);
         let status = status_to_string(torrent.status.unwrap());
         let progress = torrent.percent_done.unwrap_or(0.0).clamp(0.0, 1.0) as f64;
-        let down_speed = readble_speed(torrent.rate_download.unwrap_or(0));
-        let up_speed = readble_speed(torrent.rate_upload.unwrap_or(0));
+        let down_speed = speed(torrent.rate_download.unwrap_or(0));
+        let up_speed = speed(torrent.rate_upload.unwrap_or(0));
         let eta = readabl_eta(torrent.eta.unwrap_or(-1));
         let peers = torrent.peers_connected.unwrap_or(0).to_string();
         let seed_time = readable_time(torrent.seconds_seeding.unwrap_or(0));
-        let remaining = readable_size(total_size_bytes.saturating_sub(downloaded_bytes));
+        let remaining = size(total_size_bytes.saturating_sub(downloaded_bytes));
 
         // status dot color: accent while downloading, success when seeding
         let status_str = torrent.status.unwrap();
@@ -68,7 +101,6 @@ impl Details {
             accent
         };
 
-        let inner = outer_block.inner(area);
         frame.render_widget(outer_block, area);
 
         // ── Vertical sections ─────────────────────────────────────────────
@@ -81,20 +113,38 @@ impl Details {
         //  6  stat cards        3 lines
         //  7  spacer            1 line
         //  8  detail rows       remaining
-        let sections = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(4),
-                Constraint::Length(1),
-                Constraint::Min(2),
-            ])
-            .split(inner);
+        //
+        // The compact layout keeps the filename, status row and progress bar
+        // and puts the stat cards on one line above the detail rows.
+        let sections = if compact {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                ])
+                .split(inner)
+        } else {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Length(4),
+                    Constraint::Length(1),
+                    Constraint::Min(2),
+                ])
+                .split(inner)
+        };
+        let gauge_area = sections[if compact { 2 } else { 3 }];
+        let rows_area = sections[if compact { 4 } else { 8 }];
 
         // ── [0] Filename ──────────────────────────────────────────────────
         frame.render_widget(
@@ -127,72 +177,92 @@ impl Details {
                 .ratio(progress)
                 .label(format!("{:.1}%", progress * 100.0))
                 .use_unicode(true),
-            sections[3],
+            gauge_area,
         );
 
-        // ── [4] Bytes label ────────────────────────────────────────────────
-        frame.render_widget(
-            Paragraph::new(Line::from(vec![
-                Span::styled(
-                    downloaded.clone(),
-                    Style::default().fg(fg).bg(bg).add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(format!("  of {total_size} downloaded"), muted_style),
-            ])),
-            sections[4],
-        );
-
-        // ── [6] Stat cards ─────────────────────────────────────────────────
-        let card_areas = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-            ])
-            .split(sections[6]);
-
+        // Card title, its label on the compact line and the value.
         let cards = [
-            ("↓ Download", down_speed.as_str()),
-            ("↑ Upload", up_speed.as_str()),
-            ("Uploaded", uploaded.as_str()),
+            ("↓ Download", "↓", down_speed.as_str()),
+            ("↑ Upload", "↑", up_speed.as_str()),
+            ("Uploaded", "up", uploaded.as_str()),
             if torrent.status.unwrap() == TorrentStatus::Downloading {
-                ("time remaining", eta.as_str())
+                ("time remaining", "eta", eta.as_str())
             } else {
-                ("Seed time", seed_time.as_str())
+                ("Seed time", "seeding", seed_time.as_str())
             },
         ];
 
-        for (i, (label, value)) in cards.iter().enumerate() {
-            let card_block = Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(border_c).bg(card_bg))
-                .title(Span::styled(
-                    format!(" {label} "),
-                    Style::default().fg(muted).bg(card_bg),
-                ))
-                .style(Style::default().bg(card_bg));
-
-            let card_inner = card_block.inner(card_areas[i]);
-            frame.render_widget(card_block, card_areas[i]);
+        if compact {
+            let value_style = Style::default()
+                .fg(accent)
+                .bg(bg)
+                .add_modifier(Modifier::BOLD);
+            let spans: Vec<Span> = cards
+                .iter()
+                .enumerate()
+                .flat_map(|(i, (_, label, value))| {
+                    let gap = if i == 0 { "" } else { "  " };
+                    [
+                        Span::styled(format!("{gap}{label} "), muted_style),
+                        Span::styled(*value, value_style),
+                    ]
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(Line::from(spans)), sections[3]);
+        } else {
+            // ── [4] Bytes label ────────────────────────────────────────────
             frame.render_widget(
-                Paragraph::new(Span::styled(
-                    *value,
-                    Style::default()
-                        .fg(accent)
-                        .bg(card_bg)
-                        .add_modifier(Modifier::BOLD),
-                )),
-                card_inner,
+                Paragraph::new(Line::from(vec![
+                    Span::styled(
+                        downloaded.clone(),
+                        Style::default().fg(fg).bg(bg).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(format!("  of {total_size} downloaded"), muted_style),
+                ])),
+                sections[4],
             );
+
+            // ── [6] Stat cards ─────────────────────────────────────────────
+            let card_areas = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                ])
+                .split(sections[6]);
+
+            for (i, (label, _, value)) in cards.iter().enumerate() {
+                let card_block = Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border_c).bg(card_bg))
+                    .title(Span::styled(
+                        format!(" {label} "),
+                        Style::default().fg(muted).bg(card_bg),
+                    ))
+                    .style(Style::default().bg(card_bg));
+
+                let card_inner = card_block.inner(card_areas[i]);
+                frame.render_widget(card_block, card_areas[i]);
+                frame.render_widget(
+                    Paragraph::new(Span::styled(
+                        *value,
+                        Style::default()
+                            .fg(accent)
+                            .bg(card_bg)
+                            .add_modifier(Modifier::BOLD),
+                    )),
+                    card_inner,
+                );
+            }
         }
 
         // ── [8] Two-column detail rows ─────────────────────────────────────
         let detail_cols = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(sections[8]);
+            .split(rows_area);
 
         let val_style = Style::default().fg(fg).bg(bg).add_modifier(Modifier::BOLD);
 
@@ -209,7 +279,14 @@ impl Details {
 
         let right_rows = vec![
             Row::new(vec![
-                Cell::from(Span::styled("Connected peers", muted_style)),
+                Cell::from(Span::styled(
+                    if short_units {
+                        "Peers"
+                    } else {
+                        "Connected peers"
+                    },
+                    muted_style,
+                )),
                 Cell::from(Span::styled(peers.clone(), val_style)),
             ]),
             Row::new(vec![
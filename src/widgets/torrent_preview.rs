@@ -0,0 +1,113 @@
+use lava_torrent::bencode::BencodeElem;
+use lava_torrent::torrent::v1::Torrent;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph, Wrap},
+};
+
+use crate::{
+    config::Theme,
+    util::{readable_date, readable_size},
+};
+
+/// Metadata of a `.torrent` on disk, shown next to the file picker.
+pub struct TorrentPreview {
+    pub path: String,
+    torrent: Result<Torrent, String>,
+    /// Hashing re-encodes the whole info dict, so it is done once here.
+    info_hash: String,
+}
+
+impl TorrentPreview {
+    pub fn load(path: String) -> Self {
+        let torrent = Torrent::read_from_file(&path).map_err(|e| e.to_string());
+        let info_hash = torrent.as_ref().map(Torrent::info_hash).unwrap_or_default();
+        Self {
+            path,
+            torrent,
+            info_hash,
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let fg = Style::default().fg(Theme::color(&theme.general.foreground));
+        let muted = Style::default().fg(Theme::color(&theme.details.muted_fg));
+        let block = Block::default()
+            .title(" Info ")
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .style(fg);
+
+        let t = match &self.torrent {
+            Ok(t) => t,
+            Err(e) => {
+                frame.render_widget(
+                    Paragraph::new(format!("Unreadable torrent: {e}"))
+                        .wrap(Wrap { trim: true })
+                        .block(block),
+                    area,
+                );
+                return;
+            }
+        };
+
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{label:<12}"), muted),
+                Span::styled(value, fg),
+            ])
+        };
+        let extra = |key: &str| t.extra_fields.as_ref().and_then(|f| f.get(key));
+        let text = |key: &str| match extra(key) {
+            Some(BencodeElem::String(s)) => s.clone(),
+            Some(BencodeElem::Bytes(b)) => String::from_utf8_lossy(b).to_string(),
+            _ => "-".to_string(),
+        };
+        let created = match extra("creation date") {
+            Some(BencodeElem::Integer(ts)) => readable_date(*ts),
+            _ => "-".to_string(),
+        };
+
+        let mut lines = vec![
+            field("Name", t.name.clone()),
+            field("Info-hash", self.info_hash.clone()),
+            field("Size", readable_size(t.length.max(0) as u64)),
+            field(
+                "Pieces",
+                format!(
+                    "{} x {}",
+                    t.pieces.len(),
+                    readable_size(t.piece_length.max(0) as u64)
+                ),
+            ),
+            field("Files", t.files.as_ref().map_or(1, |f| f.len()).to_string()),
+            field(
+                "Private",
+                if t.is_private() { "Yes" } else { "No" }.to_string(),
+            ),
+            field("Created", created),
+            field("Created by", text("created by")),
+            field("Comment", text("comment")),
+        ];
+
+        let trackers: Vec<String> = match (&t.announce_list, &t.announce) {
+            (Some(tiers), _) => tiers.iter().flatten().cloned().collect(),
+            (None, Some(announce)) => vec![announce.clone()],
+            (None, None) => vec![],
+        };
+        lines.push(field("Trackers", trackers.len().to_string()));
+        for tracker in trackers {
+            lines.push(Line::from(Span::styled(format!("  {tracker}"), muted)));
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(block),
+            area,
+        );
+    }
+}
@@ -16,79 +16,248 @@ use ratatui::widgets::TableState;
 use transmission_rpc::types::Torrent;
 
 use crate::config::Theme;
+use crate::util::compact_speed;
 use crate::util::readabl_eta;
 use crate::util::readble_speed;
+use crate::util::row_at;
+use crate::util::status_to_short;
 use crate::util::status_to_string;
 
+/// The name column is never squeezed below this to make room for others.
+const MIN_NAME_WIDTH: u16 = 20;
+const COLUMN_SPACING: u16 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    Status,
+    Progress,
+    Down,
+    Up,
+    Eta,
+}
+
+impl Column {
+    const ALL: [Column; 6] = [
+        Column::Name,
+        Column::Status,
+        Column::Progress,
+        Column::Down,
+        Column::Up,
+        Column::Eta,
+    ];
+    /// Columns next to the name, most important first.
+    const BY_PRIORITY: [Column; 5] = [
+        Column::Progress,
+        Column::Down,
+        Column::Status,
+        Column::Up,
+        Column::Eta,
+    ];
+
+    /// The columns that fit next to the name in `width`, dropping the least
+    /// important ones first.
+    fn fitting(width: u16) -> Vec<Column> {
+        let mut used = MIN_NAME_WIDTH;
+        let mut kept = vec![Column::Name];
+        for column in Column::BY_PRIORITY {
+            used += column.short_width() + COLUMN_SPACING;
+            if used > width {
+                break;
+            }
+            kept.push(column);
+        }
+        Column::ALL
+            .into_iter()
+            .filter(|c| kept.contains(c))
+            .collect()
+    }
+
+    /// Room for the full header and the longest value.
+    fn full_width(self) -> u16 {
+        match self {
+            Column::Name => MIN_NAME_WIDTH,
+            Column::Status => 16,
+            Column::Progress => 8,
+            Column::Down => 14,
+            Column::Up => 12,
+            Column::Eta => 8,
+        }
+    }
+
+    /// Below this width the columns get short headers and abbreviated units
+    /// instead, and columns that don't fit are dropped.
+    fn full_table_width() -> u16 {
+        Column::ALL
+            .iter()
+            .map(|c| c.full_width() + COLUMN_SPACING)
+            .sum::<u16>()
+            - COLUMN_SPACING
+    }
+
+    fn short_width(self) -> u16 {
+        match self {
+            Column::Name => MIN_NAME_WIDTH,
+            Column::Status => 7,
+            Column::Progress => 5,
+            Column::Down | Column::Up | Column::Eta => 7,
+        }
+    }
+
+    fn width(self, short: bool) -> Constraint {
+        match (self, short) {
+            (Column::Name, _) => Constraint::Fill(1),
+            (_, false) => Constraint::Length(self.full_width()),
+            (_, true) => Constraint::Length(self.short_width()),
+        }
+    }
+
+    fn header(self, short: bool) -> &'static str {
+        match (self, short) {
+            (Column::Name, _) => "Name",
+            (Column::Status, _) => "Status",
+            (Column::Progress, false) => "Progress",
+            (Column::Progress, true) => "Done",
+            (Column::Down, false) => "Download Speed",
+            (Column::Down, true) => "Down",
+            (Column::Up, false) => "Upload Speed",
+            (Column::Up, true) => "Up",
+            (Column::Eta, _) => "ETA",
+        }
+    }
+
+    fn cell(self, torrent: &Torrent, short: bool) -> String {
+        let speed = if short { compact_speed } else { readble_speed };
+        match self {
+            Column::Name => torrent.name.clone().unwrap_or_default(),
+            Column::Status if short => status_to_short(torrent.status.unwrap()).to_string(),
+            Column::Status => status_to_string(torrent.status.unwrap()),
+            Column::Progress => {
+                let progress = torrent.percent_done.unwrap_or(0.0) * 100.0;
+                if short {
+                    format!("{:.0}%", progress)
+                } else {
+                    format!("{:.1}%", progress)
+                }
+            }
+            Column::Down => speed(torrent.rate_download.unwrap_or(0)),
+            Column::Up => speed(torrent.rate_upload.unwrap_or(0)),
+            Column::Eta => readabl_eta(torrent.eta.unwrap_or(0)),
+        }
+    }
+}
+
 pub struct TorrentTable {
     pub torrents: Vec<Torrent>,
-    pub state: TableState,
+    /// Row of the selected torrent, follows `selected` when rows move.
+    state: TableState,
+    /// Hash of the selected torrent. The poller reorders torrents and filters
+    /// drop them, so the selection is kept by hash rather than by row.
+    selected: Option<String>,
     pub scrollbar_state: ScrollbarState,
+    /// Where the table was last drawn, for mouse clicks.
+    pub area: Rect,
 }
 
 impl TorrentTable {
-    pub fn select_next(&mut self) {
-        match self.state.selected() {
-            Some(n) if n >= self.torrents.len() - 1 => self.state.select(Some(0)),
-            _ => self.state.select_next(),
+    pub fn new() -> Self {
+        Self {
+            torrents: vec![],
+            state: TableState::default(),
+            selected: None,
+            scrollbar_state: ScrollbarState::default(),
+            area: Rect::default(),
         }
     }
 
+    /// Replaces the rows, keeping the highlight on the selected torrent.
+    /// While that torrent is not in the list nothing is highlighted, so a
+    /// refresh never moves actions onto a different torrent.
+    pub fn set_torrents(&mut self, torrents: Vec<Torrent>) {
+        self.torrents = torrents;
+        self.sync_selection();
+    }
+
+    fn sync_selection(&mut self) {
+        let index = self.selected.as_deref().and_then(|hash| {
+            self.torrents
+                .iter()
+                .position(|t| t.hash_string.as_deref() == Some(hash))
+        });
+        self.state.select(index);
+    }
+
+    pub fn selected(&self) -> Option<&Torrent> {
+        self.torrents.get(self.state.selected()?)
+    }
+
+    /// Hash of the selected torrent, also while it is filtered out.
+    pub fn selected_hash(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        let torrent = index.and_then(|i| self.torrents.get(i));
+        self.selected = torrent.and_then(|t| t.hash_string.clone());
+        self.state.select(torrent.and(index));
+    }
+
+    /// Selects a torrent by hash, it is highlighted once it is in the list.
+    pub fn select_hash(&mut self, hash: String) {
+        self.selected = Some(hash);
+        self.sync_selection();
+    }
+
+    pub fn select_next(&mut self) {
+        let next = match self.state.selected() {
+            Some(n) if n + 1 < self.torrents.len() => n + 1,
+            _ => 0,
+        };
+        self.select(Some(next));
+    }
+
     pub fn select_prev(&mut self) {
-        match self.state.selected() {
-            Some(0) => self.state.select(Some(self.torrents.len() - 1)),
-            _ => self.state.select_previous(),
-        }
+        let prev = match self.state.selected() {
+            Some(n) if n > 0 => n - 1,
+            _ => self.torrents.len().saturating_sub(1),
+        };
+        self.select(Some(prev));
+    }
+
+    /// The torrent row under a mouse position, below the header.
+    pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        row_at(self.area, 1, self.state.offset(), column, row).filter(|i| *i < self.torrents.len())
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let container = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Percentage(95), Constraint::Percentage(5)])
+            .constraints(vec![Constraint::Min(0), Constraint::Length(1)])
             .split(area);
-        let header = Row::new([
-            "Name",
-            "Status",
-            "Progress",
-            "Download Speed",
-            "Upload Speed",
-            "ETA",
-        ])
-        .style(Style::new().bold());
+
+        // Inside the horizontal padding.
+        let width = container[0].width.saturating_sub(2);
+        let short = width < Column::full_table_width();
+        let columns = if short {
+            Column::fitting(width)
+        } else {
+            Column::ALL.to_vec()
+        };
+
+        let header = Row::new(columns.iter().map(|c| c.header(short))).style(Style::new().bold());
 
         let rows: Vec<Row> = self
             .torrents
             .iter()
-            .map(|torrent| {
-                let status = status_to_string(torrent.status.unwrap());
-                let progress = torrent.percent_done.unwrap_or(0.0) * 100.0;
-                let down = torrent.rate_download.unwrap_or(0);
-                let up = torrent.rate_upload.unwrap_or(0);
-                let eta = torrent.eta.unwrap_or(0);
-                Row::new([
-                    torrent.name.clone().unwrap_or_default(),
-                    status,
-                    format!("{:.1}%", progress),
-                    readble_speed(down),
-                    readble_speed(up),
-                    readabl_eta(eta),
-                ])
-            })
+            .map(|torrent| Row::new(columns.iter().map(|c| c.cell(torrent, short))))
             .collect();
 
-        let widths = [
-            Constraint::Percentage(50),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-        ];
+        let widths: Vec<Constraint> = columns.iter().map(|c| c.width(short)).collect();
 
         let block = Block::default().padding(Padding::new(1, 1, 0, 0));
         let table = Table::new(rows, widths)
             .header(header)
-            .column_spacing(2)
+            .column_spacing(COLUMN_SPACING)
             .block(block)
             .style(Theme::color(&theme.general.foreground))
             .row_highlight_style(
@@ -102,6 +271,7 @@ impl TorrentTable {
             .begin_symbol(Some("▲"))
             .end_symbol(Some("▼"));
 
+        self.area = container[0];
         frame.render_stateful_widget(table, container[0], &mut self.state);
         frame.render_stateful_widget(scrollbar, container[1], &mut self.scrollbar_state);
     }